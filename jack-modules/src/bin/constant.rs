@@ -21,6 +21,7 @@ pub fn main() {
                 .long("value")
                 .help("Value to output")
                 .required(true)
+                .allow_hyphen_values(true)
                 .takes_value(true),
         ).arg(
             Arg::with_name("NAME")
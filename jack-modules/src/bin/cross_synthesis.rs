@@ -0,0 +1,82 @@
+//! # Cross-synthesis
+//!
+//! Combine phases of the `carrier` port signal spectrum with magnitudes of the `modulator` port
+//! signal spectrum. Result goes to the `output` port.
+//! FFT size could be set via `--size` argument (power of two, 1024 by default).
+//! Output is delayed by the FFT size.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Cross-synthesis")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Combine spectra of two signals")
+        .arg(
+            Arg::with_name("SIZE")
+                .long("size")
+                .help("FFT size")
+                .default_value("1024")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let size: usize = matches
+        .value_of("SIZE")
+        .unwrap()
+        .parse()
+        .expect("Size must be a number");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = CrossSynthesis::new(size);
+
+    let carrier = client
+        .register_port("carrier", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let modulator = client
+        .register_port("modulator", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((output, carrier), modulator) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(carrier.as_slice(ps))
+            .zip(modulator.as_slice(ps))
+        {
+            *output = module.sample(Sample::from(*carrier), Sample::from(*modulator)) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -2,6 +2,8 @@
 //!
 //! Write `x` port signal delayed by seconds provided via `delay` port to the `output` port.
 //! Max delay must be provided via `--max-delay` argument to allocate appropriate buffer on start.
+//! Interpolation could be selected via `--interpolation` argument:
+//! none, linear (default), hermite, lagrange or allpass.
 
 #[macro_use]
 extern crate clap;
@@ -23,6 +25,12 @@ pub fn main() {
                 .help("Max allowed delay (seconds)")
                 .required(true)
                 .takes_value(true),
+        ).arg(
+            Arg::with_name("INTERPOLATION")
+                .long("interpolation")
+                .help("Interpolation method: none, linear, hermite, lagrange or allpass")
+                .default_value("linear")
+                .takes_value(true),
         ).arg(
             Arg::with_name("NAME")
                 .long("name")
@@ -37,6 +45,12 @@ pub fn main() {
         .parse()
         .expect("Max delay must be a number");
 
+    let interpolation: Interpolation = matches
+        .value_of("INTERPOLATION")
+        .unwrap()
+        .parse()
+        .expect("Interpolation must be one of none, linear, hermite, lagrange or allpass");
+
     let name = matches.value_of("NAME").unwrap();
 
     let (client, _status) = jack::Client::new(
@@ -44,7 +58,7 @@ pub fn main() {
         jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
     ).expect("Failed to connect to JACK");
 
-    let mut module = Delay::new(client.sample_rate(), max_delay);
+    let mut module = Delay::new(client.sample_rate(), max_delay, interpolation);
 
     let x = client
         .register_port("x", jack::AudioIn::default())
@@ -0,0 +1,77 @@
+//! # Expr
+//!
+//! Evaluate expression provided via `--expr` argument and write result to the `output` port.
+//! Every variable of the expression gets an input port named after it.
+//! See `synth_modules::expr` for the expression syntax.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Expr")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Pure function defined by expression")
+        .arg(
+            Arg::with_name("EXPR")
+                .long("expr")
+                .help("Expression to evaluate")
+                .required(true)
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let mut module = Expr::parse(matches.value_of("EXPR").unwrap())
+        .unwrap_or_else(|e| panic!("Failed to parse expression: {}", e));
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let inputs = module
+        .variables()
+        .iter()
+        .map(|variable| {
+            client
+                .register_port(variable, jack::AudioIn::default())
+                .expect("Failed to register input port")
+        }).collect::<Vec<_>>();
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let mut values = vec![0.0; inputs.len()];
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for (i, output) in output.as_mut_slice(ps).iter_mut().enumerate() {
+            for (value, input) in values.iter_mut().zip(&inputs) {
+                *value = Sample::from(input.as_slice(ps)[i]);
+            }
+            *output = module.eval(&values) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
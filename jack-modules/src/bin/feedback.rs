@@ -3,6 +3,8 @@
 //! Pass `x` port signal via feedback comb filter controller via `delay` and `gain` ports.
 //! Result goes to the `output` port.
 //! Max delay must be provided via `--max-delay` argument to allocate appropriate buffer on start.
+//! Interpolation could be selected via `--interpolation` argument:
+//! none, linear (default), hermite, lagrange or allpass.
 
 #[macro_use]
 extern crate clap;
@@ -24,6 +26,12 @@ pub fn main() {
                 .help("Max allowed delay (seconds)")
                 .required(true)
                 .takes_value(true),
+        ).arg(
+            Arg::with_name("INTERPOLATION")
+                .long("interpolation")
+                .help("Interpolation method: none, linear, hermite, lagrange or allpass")
+                .default_value("linear")
+                .takes_value(true),
         ).arg(
             Arg::with_name("NAME")
                 .long("name")
@@ -38,6 +46,12 @@ pub fn main() {
         .parse()
         .expect("Max delay must be a number");
 
+    let interpolation: Interpolation = matches
+        .value_of("INTERPOLATION")
+        .unwrap()
+        .parse()
+        .expect("Interpolation must be one of none, linear, hermite, lagrange or allpass");
+
     let name = matches.value_of("NAME").unwrap();
 
     let (client, _status) = jack::Client::new(
@@ -45,7 +59,7 @@ pub fn main() {
         jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
     ).expect("Failed to connect to JACK");
 
-    let mut module = Feedback::new(client.sample_rate(), max_delay);
+    let mut module = Feedback::new(client.sample_rate(), max_delay, interpolation);
 
     let x = client
         .register_port("x", jack::AudioIn::default())
@@ -0,0 +1,118 @@
+//! # FM
+//!
+//! Four operators FM voice with the frequency provided via the `frequency` port, modulation index
+//! via the `index` port and operator 4 feedback via the `feedback` port.
+//! Result goes to the `output` port.
+//! Algorithm must be selected via `--algorithm` argument:
+//!
+//! * 1 -- 4 -> 3 -> 2 -> 1
+//! * 2 -- (3 + 4) -> 2 -> 1
+//! * 3 -- (3 -> 2) + 4 -> 1
+//! * 4 -- (4 -> 3) + 2 -> 1
+//! * 5 -- (2 -> 1) + (4 -> 3)
+//! * 6 -- 4 -> (1 + 2 + 3)
+//! * 7 -- 1 + 2 + (4 -> 3)
+//! * 8 -- 1 + 2 + 3 + 4
+//!
+//! Operators frequency ratios could be provided via `--ratios` argument as a comma-separated list,
+//! e.g. `--ratios 1,2,3.5,7`.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::operator::OPERATORS;
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("FM")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Four operators FM voice")
+        .arg(
+            Arg::with_name("ALGORITHM")
+                .long("algorithm")
+                .help("Number of the operators algorithm")
+                .required(true)
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("RATIOS")
+                .long("ratios")
+                .help("Comma-separated operators frequency ratios")
+                .default_value("1,1,1,1")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let algorithm: usize = matches
+        .value_of("ALGORITHM")
+        .unwrap()
+        .parse()
+        .expect("Algorithm must be a number");
+
+    let mut ratios = [1.0; OPERATORS];
+    for (ratio, value) in ratios
+        .iter_mut()
+        .zip(matches.value_of("RATIOS").unwrap().split(','))
+    {
+        *ratio = value.parse().expect("Ratio must be a number");
+    }
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = FMVoice::new(client.sample_rate(), algorithm, ratios);
+
+    let frequency = client
+        .register_port("frequency", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let index = client
+        .register_port("index", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let feedback = client
+        .register_port("feedback", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for (((output, frequency), index), feedback) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(frequency.as_slice(ps))
+            .zip(index.as_slice(ps))
+            .zip(feedback.as_slice(ps))
+        {
+            *output = module.sample(
+                Sample::from(*frequency),
+                Sample::from(*index),
+                Sample::from(*feedback),
+            ) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -11,6 +11,20 @@
 //! * unit     -- [-1, 1] -> [0, 1]
 //! * circle   -- [-1, 1] -> [-π, π]
 //! * cheb<N>  -- Chebyshev polynomial of the first kind and degree N
+//! * abs      -- |x|
+//! * sign     -- -1, 0 or 1
+//! * floor    -- round down
+//! * ceil     -- round up
+//! * fract    -- x - floor(x)
+//! * exp      -- e^x
+//! * log      -- natural logarithm
+//! * sqrt     -- square root
+//! * tanh     -- hyperbolic tangent
+//! * not      -- 1 if x <= 0, 0 otherwise
+//! * mtof     -- MIDI note number -> Hz
+//! * ftom     -- Hz -> MIDI note number
+//! * dbamp    -- dB -> amplitude
+//! * ampdb    -- amplitude -> dB
 
 #[macro_use]
 extern crate clap;
@@ -53,6 +67,20 @@ pub fn main() {
         "cheb4" => cheb4,
         "cheb5" => cheb5,
         "cheb6" => cheb6,
+        "abs" => abs,
+        "sign" => sign,
+        "floor" => floor,
+        "ceil" => ceil,
+        "fract" => fract,
+        "exp" => exp,
+        "log" => log,
+        "sqrt" => sqrt,
+        "tanh" => tanh,
+        "not" => not,
+        "mtof" => midi_to_hz,
+        "ftom" => hz_to_midi,
+        "dbamp" => db_to_amp,
+        "ampdb" => amp_to_db,
         name => panic!("Unknown function: {}", name),
     };
 
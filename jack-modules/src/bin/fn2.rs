@@ -8,6 +8,18 @@
 //! * sub       -- x - y
 //! * div       -- x / y
 //! * rectangle -- x = phase, y = width or duty cycle
+//! * mod       -- x mod y, result has the sign of y
+//! * pow       -- x^y
+//! * min       -- min(x, y)
+//! * max       -- max(x, y)
+//! * lt        -- 1 if x < y, 0 otherwise
+//! * gt        -- 1 if x > y, 0 otherwise
+//! * le        -- 1 if x <= y, 0 otherwise
+//! * ge        -- 1 if x >= y, 0 otherwise
+//! * eq        -- 1 if x = y, 0 otherwise
+//! * ne        -- 1 if x != y, 0 otherwise
+//! * and       -- 1 if both x > 0 and y > 0, 0 otherwise
+//! * or        -- 1 if either x > 0 or y > 0, 0 otherwise
 
 #[macro_use]
 extern crate clap;
@@ -43,6 +55,18 @@ pub fn main() {
         "sub" => sub,
         "div" => div,
         "rectangle" => rectangle,
+        "mod" => modulo,
+        "pow" => pow,
+        "min" => min,
+        "max" => max,
+        "lt" => lt,
+        "gt" => gt,
+        "le" => le,
+        "ge" => ge,
+        "eq" => eq,
+        "ne" => ne,
+        "and" => and,
+        "or" => or,
         name => panic!("Unknown function: {}", name),
     };
 
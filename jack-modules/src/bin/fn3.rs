@@ -3,7 +3,10 @@
 //! Transform `x` and `y` and `z` ports signals with a ternary function and write result to the `output` port.
 //! Function must be selected via `--fn` argument:
 //!
-//! * range -- [-1, 1] -> [y, z]
+//! * range  -- [-1, 1] -> [y, z]
+//! * clamp  -- limit x to [y, z]
+//! * select -- y if x > 0, z otherwise
+//! * mix    -- crossfade from x to y by z in [0, 1]
 
 #[macro_use]
 extern crate clap;
@@ -35,6 +38,9 @@ pub fn main() {
 
     let f = match matches.value_of("FN").unwrap() {
         "range" => range,
+        "clamp" => clamp,
+        "select" => select,
+        "mix" => mix,
         name => panic!("Unknown function: {}", name),
     };
 
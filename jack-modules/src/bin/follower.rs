@@ -0,0 +1,94 @@
+//! # Follower
+//!
+//! Track amplitude of the `x` port signal with attack and release times (seconds) provided via
+//! `attack` and `release` ports. Result goes to the `output` port.
+//! Mode could be selected via `--mode` argument:
+//!
+//! * peak -- follow absolute value of the signal (default)
+//! * rms  -- follow root mean square of the signal
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::envelope::Mode;
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Follower")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Envelope follower")
+        .arg(
+            Arg::with_name("MODE")
+                .long("mode")
+                .help("Peak or RMS")
+                .default_value("peak")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let mode = match matches.value_of("MODE").unwrap() {
+        "peak" => Mode::Peak,
+        "rms" => Mode::RMS,
+        mode => panic!("Unknown mode: {}", mode),
+    };
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = EnvelopeFollower::new(client.sample_rate(), mode);
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let attack = client
+        .register_port("attack", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let release = client
+        .register_port("release", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for (((output, x), attack), release) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(x.as_slice(ps))
+            .zip(attack.as_slice(ps))
+            .zip(release.as_slice(ps))
+        {
+            *output = module.sample(
+                Sample::from(*x),
+                Sample::from(*attack),
+                Sample::from(*release),
+            ) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
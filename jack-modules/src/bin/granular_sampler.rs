@@ -0,0 +1,103 @@
+//! # Granular Sampler
+//!
+//! Granular synthesis over WAV file provided via `--path` argument. Grains `size` (seconds),
+//! `density` (grains per second), `position` (relative to the sample length), `pitch` (playback
+//! rate) and `spray` (random position displacement) are controlled via corresponding ports.
+//! Result goes to the `output` port.
+//! Multichannel files are mixed down to mono.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Granular Sampler")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Granular synthesis over WAV file")
+        .arg(
+            Arg::with_name("PATH")
+                .long("path")
+                .help("Path to the WAV file")
+                .required(true)
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let path = matches.value_of("PATH").unwrap();
+    let (buffer, buffer_sample_rate) =
+        jack_modules::wav::read(path).expect("Failed to read WAV file");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = Granular::from_buffer(client.sample_rate(), buffer, buffer_sample_rate);
+
+    let size = client
+        .register_port("size", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let density = client
+        .register_port("density", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let position = client
+        .register_port("position", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let pitch = client
+        .register_port("pitch", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let spray = client
+        .register_port("spray", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for (((((output, size), density), position), pitch), spray) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(size.as_slice(ps))
+            .zip(density.as_slice(ps))
+            .zip(position.as_slice(ps))
+            .zip(pitch.as_slice(ps))
+            .zip(spray.as_slice(ps))
+        {
+            *output = module.sample(
+                0.0,
+                Sample::from(*size),
+                Sample::from(*density),
+                Sample::from(*position),
+                Sample::from(*pitch),
+                Sample::from(*spray),
+            ) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -0,0 +1,70 @@
+//! # Hilbert
+//!
+//! Convert the `x` port signal into quadrature pair with 90° phase difference and write it to the
+//! `real` and `imaginary` ports.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Hilbert")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Hilbert transform all-pass pair")
+        .arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = Hilbert::new();
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut real = client
+        .register_port("real", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let mut imaginary = client
+        .register_port("imaginary", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((real, imaginary), x) in real
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(imaginary.as_mut_slice(ps))
+            .zip(x.as_slice(ps))
+        {
+            let (re, im) = module.sample(Sample::from(*x));
+            *real = re as f32;
+            *imaginary = im as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
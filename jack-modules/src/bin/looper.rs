@@ -0,0 +1,110 @@
+//! # Looper
+//!
+//! Record `x` port signal into the loop up to `--max-length` seconds long and play it back.
+//! Trigger on the `record` port cycles through recording, playing and overdubbing states, trigger on
+//! the `clear` port erases the loop. When overdubbing, the loop is multiplied by the `feedback` port
+//! signal. Result goes to the `output` port.
+//! With `--sync` flag, triggers are applied only on the next `clock` port trigger.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Looper")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Live looper with overdub")
+        .arg(
+            Arg::with_name("MAX_LENGTH")
+                .long("max-length")
+                .help("Max allowed loop length (seconds)")
+                .required(true)
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("SYNC")
+                .long("sync")
+                .help("Apply triggers on clock"),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let max_length: Sample = matches
+        .value_of("MAX_LENGTH")
+        .unwrap()
+        .parse()
+        .expect("Max length must be a number");
+
+    let is_synced = matches.is_present("SYNC");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = Looper::new(client.sample_rate(), max_length, is_synced);
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let record = client
+        .register_port("record", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let clear = client
+        .register_port("clear", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let feedback = client
+        .register_port("feedback", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let clock = client
+        .register_port("clock", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for (((((output, x), record), clear), feedback), clock) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(x.as_slice(ps))
+            .zip(record.as_slice(ps))
+            .zip(clear.as_slice(ps))
+            .zip(feedback.as_slice(ps))
+            .zip(clock.as_slice(ps))
+        {
+            *output = module.sample(
+                Sample::from(*x),
+                Sample::from(*record),
+                Sample::from(*clear),
+                Sample::from(*feedback),
+                Sample::from(*clock),
+            ) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -0,0 +1,152 @@
+//! # Modal resonator
+//!
+//! Excite bank of resonators by `x` port signal and write their response to the `output` port.
+//! Frequencies of resonances are relative to the fundamental provided via `frequency` port.
+//!
+//! Resonances are defined by comma-separated lists of frequency ratios, decay times (seconds to
+//! decay by 60 dB) and amplitudes provided via `--ratios`, `--decays` and `--amplitudes` arguments.
+//! Single decay or amplitude applies to all resonances.
+//! Alternatively resonances could be loaded via `--file` argument from a text file with
+//! whitespace-separated ratio, decay and amplitude on every line. Empty lines and lines starting
+//! with `#` are ignored.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use std::fs;
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Modal resonator")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Bank of decaying sine resonators")
+        .arg(
+            Arg::with_name("RATIOS")
+                .long("ratios")
+                .help("Comma-separated frequency ratios")
+                .default_value("1")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("DECAYS")
+                .long("decays")
+                .help("Comma-separated decay times (seconds)")
+                .default_value("1")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("AMPLITUDES")
+                .long("amplitudes")
+                .help("Comma-separated amplitudes")
+                .default_value("1")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("FILE")
+                .long("file")
+                .help("Path to the file with resonances")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let resonances = match matches.value_of("FILE") {
+        Some(path) => read_resonances(path),
+        None => {
+            let ratios = parse_list(matches.value_of("RATIOS").unwrap(), "Ratio");
+            let decays = parse_list(matches.value_of("DECAYS").unwrap(), "Decay");
+            let amplitudes = parse_list(matches.value_of("AMPLITUDES").unwrap(), "Amplitude");
+            let pick = |values: &[Sample], i: usize, what: &str| match values.len() {
+                1 => values[0],
+                n if n == ratios.len() => values[i],
+                _ => panic!("{} count must be 1 or match ratios count", what),
+            };
+            ratios
+                .iter()
+                .enumerate()
+                .map(|(i, &ratio)| Resonance {
+                    ratio,
+                    decay: pick(&decays, i, "Decays"),
+                    amplitude: pick(&amplitudes, i, "Amplitudes"),
+                }).collect::<Vec<_>>()
+        }
+    };
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = Modal::new(client.sample_rate(), &resonances);
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let frequency = client
+        .register_port("frequency", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((output, x), frequency) in output
+            .as_mut_slice(ps)
+            .iter_mut()
+            .zip(x.as_slice(ps))
+            .zip(frequency.as_slice(ps))
+        {
+            *output = module.sample(Sample::from(*x), Sample::from(*frequency)) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
+
+fn parse_list(list: &str, what: &str) -> Vec<Sample> {
+    list.split(',')
+        .map(|x| {
+            x.trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("{} must be a number", what))
+        }).collect()
+}
+
+fn read_resonances(path: &str) -> Vec<Resonance> {
+    fs::read_to_string(path)
+        .expect("Failed to read resonances file")
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let values = line
+                .split_whitespace()
+                .map(|x| x.parse().expect("Resonance values must be numbers"))
+                .collect::<Vec<Sample>>();
+            match values[..] {
+                [ratio, decay, amplitude] => Resonance {
+                    ratio,
+                    decay,
+                    amplitude,
+                },
+                _ => panic!("Resonance must be defined by ratio, decay and amplitude"),
+            }
+        }).collect()
+}
@@ -0,0 +1,111 @@
+//! # Multi-tap delay
+//!
+//! Write sum of `x` port signal delayed by several taps to the `output` port.
+//! Every tap has its own delay time (seconds) and gain provided via `time_N` and `gain_N` ports,
+//! where N is a tap number starting from 1.
+//! Number of taps could be set via `--taps` argument (4 by default).
+//! Max delay must be provided via `--max-delay` argument to allocate appropriate buffer on start.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Multi-tap delay")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Delay signal by several taps with individual time and gain")
+        .arg(
+            Arg::with_name("MAX_DELAY")
+                .long("max-delay")
+                .help("Max allowed delay (seconds)")
+                .required(true)
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("TAPS")
+                .long("taps")
+                .help("Number of taps")
+                .default_value("4")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let max_delay: Sample = matches
+        .value_of("MAX_DELAY")
+        .unwrap()
+        .parse()
+        .expect("Max delay must be a number");
+
+    let taps: usize = matches
+        .value_of("TAPS")
+        .unwrap()
+        .parse()
+        .expect("Taps must be a number");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = MultiTap::new(client.sample_rate(), max_delay, taps);
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let (time_ports, gain_ports): (Vec<_>, Vec<_>) = (1..=taps)
+        .map(|i| {
+            let time = client
+                .register_port(&format!("time_{}", i), jack::AudioIn::default())
+                .expect("Failed to register input port");
+            let gain = client
+                .register_port(&format!("gain_{}", i), jack::AudioIn::default())
+                .expect("Failed to register input port");
+            (time, gain)
+        }).unzip();
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let mut times = vec![0.0; taps];
+    let mut gains = vec![0.0; taps];
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for (i, (output, x)) in output
+            .as_mut_slice(ps)
+            .iter_mut()
+            .zip(x.as_slice(ps))
+            .enumerate()
+        {
+            for (time, port) in times.iter_mut().zip(&time_ports) {
+                *time = Sample::from(port.as_slice(ps)[i]);
+            }
+            for (gain, port) in gains.iter_mut().zip(&gain_ports) {
+                *gain = Sample::from(port.as_slice(ps)[i]);
+            }
+            *output = module.sample(Sample::from(*x), &times, &gains) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
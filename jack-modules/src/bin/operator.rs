@@ -0,0 +1,78 @@
+//! # Operator
+//!
+//! Sine oscillator with the frequency provided via the `frequency` port, phase modulated by the
+//! `modulation` port signal (radians) and fed back to itself scaled by the `feedback` port signal.
+//! Result goes to the `output` port.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Operator")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Phase-modulated sine oscillator with feedback")
+        .arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = Operator::new(client.sample_rate());
+
+    let frequency = client
+        .register_port("frequency", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let modulation = client
+        .register_port("modulation", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let feedback = client
+        .register_port("feedback", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for (((output, frequency), modulation), feedback) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(frequency.as_slice(ps))
+            .zip(modulation.as_slice(ps))
+            .zip(feedback.as_slice(ps))
+        {
+            *output = module.sample(
+                Sample::from(*frequency),
+                Sample::from(*modulation),
+                Sample::from(*feedback),
+            ) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -2,6 +2,8 @@
 //!
 //! Oscillate as a saw wave in interval [-1, 1] with the frequency provided via the `frequency` port
 //! and write current phase into the `phase` port.
+//! With `--sync` flag, `reset` port is registered and phase is reset to the initial one provided
+//! via `--phase` argument (0 by default) when its signal crosses zero going up.
 
 #[macro_use]
 extern crate clap;
@@ -18,6 +20,17 @@ pub fn main() {
         .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
         .about("Generate phase in [-1, 1] interval with the input frequency")
         .arg(
+            Arg::with_name("PHASE")
+                .long("phase")
+                .help("Initial phase in [-1, 1] interval")
+                .default_value("0")
+                .allow_hyphen_values(true)
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("SYNC")
+                .long("sync")
+                .help("Register reset port for hard sync"),
+        ).arg(
             Arg::with_name("NAME")
                 .long("name")
                 .help("Client name")
@@ -25,6 +38,14 @@ pub fn main() {
                 .takes_value(true),
         ).get_matches();
 
+    let initial_phase: Sample = matches
+        .value_of("PHASE")
+        .unwrap()
+        .parse()
+        .expect("Phase must be a number");
+
+    let is_synced = matches.is_present("SYNC");
+
     let name = matches.value_of("NAME").unwrap();
 
     let (client, _status) = jack::Client::new(
@@ -32,23 +53,32 @@ pub fn main() {
         jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
     ).expect("Failed to connect to JACK");
 
-    let mut module = Phasor::new(client.sample_rate());
+    let mut module = Phasor::new(client.sample_rate(), initial_phase);
 
     let frequency = client
         .register_port("frequency", jack::AudioIn::default())
         .expect("Failed to register input port");
 
+    let reset = if is_synced {
+        Some(
+            client
+                .register_port("reset", jack::AudioIn::default())
+                .expect("Failed to register input port"),
+        )
+    } else {
+        None
+    };
+
     let mut phase = client
         .register_port("phase", jack::AudioOut::default())
         .expect("Failed to register output port");
 
     let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
-        for (phase, frequency) in phase
-            .as_mut_slice(ps)
-            .into_iter()
-            .zip(frequency.as_slice(ps))
-        {
-            *phase = module.sample(Sample::from(*frequency)) as f32;
+        let frequency = frequency.as_slice(ps);
+        let reset = reset.as_ref().map(|reset| reset.as_slice(ps));
+        for (i, phase) in phase.as_mut_slice(ps).into_iter().enumerate() {
+            let reset = reset.map_or(0.0, |reset| Sample::from(reset[i]));
+            *phase = module.sample(Sample::from(frequency[i]), reset) as f32;
         }
         jack::Control::Continue
     };
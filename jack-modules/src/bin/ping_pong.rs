@@ -0,0 +1,111 @@
+//! # Ping-pong delay
+//!
+//! Stereo delay with echoes bouncing between channels. Write `input_1` and `input_2` ports signals
+//! delayed by seconds provided via `delay` port to `output_1` and `output_2` ports respectively,
+//! feeding every channel echoes back into the other one with `feedback` port gain.
+//! Feedback path is low-passed with cut-off frequency provided via `damping` port.
+//! Max delay must be provided via `--max-delay` argument to allocate appropriate buffer on start.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Ping-pong delay")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Stereo delay with damped cross-feedback")
+        .arg(
+            Arg::with_name("MAX_DELAY")
+                .long("max-delay")
+                .help("Max allowed delay (seconds)")
+                .required(true)
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let max_delay: Sample = matches
+        .value_of("MAX_DELAY")
+        .unwrap()
+        .parse()
+        .expect("Max delay must be a number");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = PingPong::new(client.sample_rate(), max_delay);
+
+    let input_1 = client
+        .register_port("input_1", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let input_2 = client
+        .register_port("input_2", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let delay = client
+        .register_port("delay", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let feedback = client
+        .register_port("feedback", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let damping = client
+        .register_port("damping", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output_1 = client
+        .register_port("output_1", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let mut output_2 = client
+        .register_port("output_2", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((((((output_1, output_2), input_1), input_2), delay), feedback), damping) in output_1
+            .as_mut_slice(ps)
+            .iter_mut()
+            .zip(output_2.as_mut_slice(ps))
+            .zip(input_1.as_slice(ps))
+            .zip(input_2.as_slice(ps))
+            .zip(delay.as_slice(ps))
+            .zip(feedback.as_slice(ps))
+            .zip(damping.as_slice(ps))
+        {
+            let (left, right) = module.sample(
+                Sample::from(*input_1),
+                Sample::from(*input_2),
+                Sample::from(*delay),
+                Sample::from(*feedback),
+                Sample::from(*damping),
+            );
+            *output_1 = left as f32;
+            *output_2 = right as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -0,0 +1,95 @@
+//! # Pitch
+//!
+//! Track pitch of the `x` port signal and write estimated frequency to the `frequency` port and
+//! estimation confidence (0..1) to the `confidence` port.
+//! Detectable frequencies range could be set via `--min-frequency` and `--max-frequency` arguments.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Pitch")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("YIN pitch tracker")
+        .arg(
+            Arg::with_name("MIN_FREQUENCY")
+                .long("min-frequency")
+                .help("Lowest detectable frequency (Hz)")
+                .default_value("50")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("MAX_FREQUENCY")
+                .long("max-frequency")
+                .help("Highest detectable frequency (Hz)")
+                .default_value("2000")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let min_frequency: Sample = matches
+        .value_of("MIN_FREQUENCY")
+        .unwrap()
+        .parse()
+        .expect("Min frequency must be a number");
+
+    let max_frequency: Sample = matches
+        .value_of("MAX_FREQUENCY")
+        .unwrap()
+        .parse()
+        .expect("Max frequency must be a number");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = PitchTracker::new(client.sample_rate(), min_frequency, max_frequency);
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut frequency = client
+        .register_port("frequency", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let mut confidence = client
+        .register_port("confidence", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((frequency, confidence), x) in frequency
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(confidence.as_mut_slice(ps))
+            .zip(x.as_slice(ps))
+        {
+            let (f, c) = module.sample(Sample::from(*x));
+            *frequency = f as f32;
+            *confidence = c as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
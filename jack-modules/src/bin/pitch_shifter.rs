@@ -0,0 +1,81 @@
+//! # Pitch shifter
+//!
+//! Write `x` port signal shifted by the number of semitones provided via `shift` port to the
+//! `output` port. Length of the delay sweep could be set via `--window` argument (seconds, 0.05 by
+//! default).
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Pitch shifter")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Shift signal pitch by semitones")
+        .arg(
+            Arg::with_name("WINDOW")
+                .long("window")
+                .help("Delay sweep length (seconds)")
+                .default_value("0.05")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let window: Sample = matches
+        .value_of("WINDOW")
+        .unwrap()
+        .parse()
+        .expect("Window must be a number");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = PitchShifter::new(client.sample_rate(), window);
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let shift = client
+        .register_port("shift", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((output, x), shift) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(x.as_slice(ps))
+            .zip(shift.as_slice(ps))
+        {
+            *output = module.sample(Sample::from(*x), Sample::from(*shift)) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
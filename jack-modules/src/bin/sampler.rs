@@ -0,0 +1,102 @@
+//! # Sampler
+//!
+//! Play WAV file provided via `--path` argument when `trigger` port signal crosses zero going up.
+//! Playback speed and pitch is controlled via `rate` port, played region is selected via `start`
+//! and `end` ports (relative to the sample length), and looping is enabled by positive `loop` port
+//! signal. Result goes to the `output` port.
+//! Multichannel files are mixed down to mono.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Sampler")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Play WAV file with variable rate and loop points")
+        .arg(
+            Arg::with_name("PATH")
+                .long("path")
+                .help("Path to the WAV file")
+                .required(true)
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let path = matches.value_of("PATH").unwrap();
+    let (buffer, buffer_sample_rate) =
+        jack_modules::wav::read(path).expect("Failed to read WAV file");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = Sampler::new(client.sample_rate(), buffer, buffer_sample_rate);
+
+    let trigger = client
+        .register_port("trigger", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let rate = client
+        .register_port("rate", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let start = client
+        .register_port("start", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let end = client
+        .register_port("end", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let is_looped = client
+        .register_port("loop", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for (((((output, trigger), rate), start), end), is_looped) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(trigger.as_slice(ps))
+            .zip(rate.as_slice(ps))
+            .zip(start.as_slice(ps))
+            .zip(end.as_slice(ps))
+            .zip(is_looped.as_slice(ps))
+        {
+            *output = module.sample(
+                Sample::from(*trigger),
+                Sample::from(*rate),
+                Sample::from(*start),
+                Sample::from(*end),
+                Sample::from(*is_looped),
+            ) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -0,0 +1,82 @@
+//! # Spectral freeze
+//!
+//! Sustain spectrum of the `x` port signal while the `freeze` port signal is positive.
+//! Result goes to the `output` port.
+//! FFT size could be set via `--size` argument (power of two, 1024 by default).
+//! Output is delayed by the FFT size.
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Spectral freeze")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Freeze spectrum of the signal")
+        .arg(
+            Arg::with_name("SIZE")
+                .long("size")
+                .help("FFT size")
+                .default_value("1024")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let size: usize = matches
+        .value_of("SIZE")
+        .unwrap()
+        .parse()
+        .expect("Size must be a number");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = SpectralFreeze::new(size);
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let freeze = client
+        .register_port("freeze", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((output, x), freeze) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(x.as_slice(ps))
+            .zip(freeze.as_slice(ps))
+        {
+            *output = module.sample(Sample::from(*x), Sample::from(*freeze)) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -0,0 +1,81 @@
+//! # Vocoder
+//!
+//! Channel vocoder. Spectral envelope of the `modulator` port signal is imposed on the `carrier`
+//! port signal through the bank of band-pass filters. Result goes to the `output` port.
+//! Number of bands could be set via `--bands` argument (16 by default).
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Vocoder")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Channel vocoder")
+        .arg(
+            Arg::with_name("BANDS")
+                .long("bands")
+                .help("Number of bands")
+                .default_value("16")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let bands: usize = matches
+        .value_of("BANDS")
+        .unwrap()
+        .parse()
+        .expect("Bands must be a number");
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = Vocoder::new(client.sample_rate(), bands);
+
+    let modulator = client
+        .register_port("modulator", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let carrier = client
+        .register_port("carrier", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((output, modulator), carrier) in output
+            .as_mut_slice(ps)
+            .into_iter()
+            .zip(modulator.as_slice(ps))
+            .zip(carrier.as_slice(ps))
+        {
+            *output = module.sample(Sample::from(*modulator), Sample::from(*carrier)) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
@@ -0,0 +1,101 @@
+//! # Waveguide
+//!
+//! Excite waveguide tuned to the `frequency` port value by `x` port signal and write its response
+//! to the `output` port. Waveguide decays by 60 dB in seconds provided via `decay` port and loses
+//! high frequencies according to the `damping` port value (0 is bright, 1 is dull).
+//! Kind could be selected via `--kind` argument:
+//!
+//! * string -- both ends are fixed, all harmonics are present (default)
+//! * tube   -- one end is open, only odd harmonics are present
+
+#[macro_use]
+extern crate clap;
+extern crate jack;
+extern crate jack_modules;
+extern crate synth_modules;
+
+use clap::{App, Arg};
+use synth_modules::waveguide::Kind;
+use synth_modules::prelude::*;
+
+pub fn main() {
+    let matches = App::new("Waveguide")
+        .version(crate_version!())
+        .author("Ruslan Prokopchuk <fer.obbee@gmail.com>")
+        .about("Waveguide physical model")
+        .arg(
+            Arg::with_name("KIND")
+                .long("kind")
+                .help("String or tube")
+                .default_value("string")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("NAME")
+                .long("name")
+                .help("Client name")
+                .required(true)
+                .takes_value(true),
+        ).get_matches();
+
+    let kind = match matches.value_of("KIND").unwrap() {
+        "string" => Kind::String,
+        "tube" => Kind::Tube,
+        kind => panic!("Unknown kind: {}", kind),
+    };
+
+    let name = matches.value_of("NAME").unwrap();
+
+    let (client, _status) = jack::Client::new(
+        name,
+        jack::ClientOptions::NO_START_SERVER | jack::ClientOptions::USE_EXACT_NAME,
+    ).expect("Failed to connect to JACK");
+
+    let mut module = Waveguide::new(client.sample_rate(), kind);
+
+    let x = client
+        .register_port("x", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let frequency = client
+        .register_port("frequency", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let decay = client
+        .register_port("decay", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let damping = client
+        .register_port("damping", jack::AudioIn::default())
+        .expect("Failed to register input port");
+
+    let mut output = client
+        .register_port("output", jack::AudioOut::default())
+        .expect("Failed to register output port");
+
+    let process_callback = move |_: &jack::Client, ps: &jack::ProcessScope| -> jack::Control {
+        for ((((output, x), frequency), decay), damping) in output
+            .as_mut_slice(ps)
+            .iter_mut()
+            .zip(x.as_slice(ps))
+            .zip(frequency.as_slice(ps))
+            .zip(decay.as_slice(ps))
+            .zip(damping.as_slice(ps))
+        {
+            *output = module.sample(
+                Sample::from(*x),
+                Sample::from(*frequency),
+                Sample::from(*decay),
+                Sample::from(*damping),
+            ) as f32;
+        }
+        jack::Control::Continue
+    };
+    let process = jack::ClosureProcessHandler::new(process_callback);
+
+    let (notification, is_alive) = jack_modules::notification::Notification::new();
+    let active_client = client.activate_async(notification, process).unwrap();
+
+    assert!(is_alive.recv().is_err());
+
+    active_client.deactivate().unwrap();
+}
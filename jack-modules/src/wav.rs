@@ -0,0 +1,31 @@
+//! # WAV
+//!
+//! Load WAV files into memory for sample-based modules.
+use hound;
+use synth_modules::sample::Sample;
+
+/// Read WAV file mixing all its channels down to mono.
+/// Return samples and sample rate of the file.
+pub fn read(path: &str) -> Result<(Vec<Sample>, usize), hound::Error> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .map(|s| s.map(Sample::from))
+            .collect::<Result<Vec<_>, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1_u64 << (spec.bits_per_sample - 1)) as Sample;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| Sample::from(s) / scale))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+    let channels = spec.channels as usize;
+    let buffer = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<Sample>() / channels as Sample)
+        .collect();
+    Ok((buffer, spec.sample_rate as usize))
+}
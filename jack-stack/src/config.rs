@@ -18,19 +18,147 @@ pub struct PrimitiveWord {
     /// Arguments which are set by constructing word with slashes,
     /// e.g. `delay/60` when `slash_args: ["--max-delay"]` would lead to passing
     /// `--max-delay 60` to the module command.
-    /// TODO Support passing slash args positionally.
-    pub slash_args: Option<Vec<String>>,
+    /// Values are matched to args by position or by name, e.g. `delay/max=60`.
+    /// See `SlashArg` for details.
+    pub slash_args: Option<Vec<SlashArg>>,
     /// Arbitrary arguments to pass to the module command.
     pub extra_args: Option<Vec<String>>,
+    /// Treat slash arg as an expression (see `synth_modules::expr`) and use its variables as module
+    /// inputs instead of `inputs`. The whole rest of the word after the first slash is taken as
+    /// a single slash arg, so division could be used in the expression, e.g. `expr/x/2`.
+    #[serde(default)]
+    pub expression_inputs: bool,
+}
+
+/// Slash arg definition.
+/// NOTE TOML doesn't allow to mix strings and tables in the same array, so when one of word's slash
+/// args needs a table form all of them should use it.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum SlashArg {
+    /// Just a flag, e.g. `"--max-delay"`. Its name is the flag without leading dashes.
+    Flag(String),
+    /// Full form, e.g. `{ name = "max", flag = "--max-delay", type = "number", required = true }`.
+    Spec(SlashArgSpec),
+}
+
+#[derive(Deserialize)]
+pub struct SlashArgSpec {
+    /// Name to refer to the arg, e.g. `delay/max=60`.
+    /// Could be omitted for flagged args, then it's the flag without leading dashes.
+    pub name: Option<String>,
+    /// Flag to pass the value with. When omitted the value is passed as a positional argument.
+    pub flag: Option<String>,
+    /// Type of the value to validate before spawning a module.
+    #[serde(default, rename = "type")]
+    pub kind: SlashArgType,
+    /// Allowed values, if value must be one of them.
+    pub values: Option<Vec<String>>,
+    /// Value to pass when it is not set.
+    pub default: Option<String>,
+    /// Whether word can't be used without this arg. Makes no sense with `default`.
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SlashArgType {
+    String,
+    Number,
+    Integer,
+}
+
+// Deriving `Default` for enums needs a newer compiler than the rest of the code.
+#[allow(clippy::derivable_impls)]
+impl Default for SlashArgType {
+    fn default() -> Self {
+        SlashArgType::String
+    }
+}
+
+impl SlashArg {
+    pub fn name(&self) -> &str {
+        match self {
+            SlashArg::Flag(flag) => flag.trim_start_matches('-'),
+            SlashArg::Spec(spec) => match (&spec.name, &spec.flag) {
+                (Some(name), _) => name,
+                (None, Some(flag)) => flag.trim_start_matches('-'),
+                (None, None) => "",
+            },
+        }
+    }
+
+    pub fn flag(&self) -> Option<&str> {
+        match self {
+            SlashArg::Flag(flag) => Some(flag),
+            SlashArg::Spec(spec) => spec.flag.as_deref(),
+        }
+    }
+
+    pub fn default(&self) -> Option<&str> {
+        match self {
+            SlashArg::Flag(_) => None,
+            SlashArg::Spec(spec) => spec.default.as_deref(),
+        }
+    }
+
+    pub fn is_required(&self) -> bool {
+        match self {
+            SlashArg::Flag(_) => false,
+            SlashArg::Spec(spec) => spec.required,
+        }
+    }
+
+    /// Check that value matches arg type and allowed values.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let spec = match self {
+            SlashArg::Flag(_) => return Ok(()),
+            SlashArg::Spec(spec) => spec,
+        };
+        let is_valid_type = match spec.kind {
+            SlashArgType::String => true,
+            SlashArgType::Number => value.parse::<f64>().is_ok(),
+            SlashArgType::Integer => value.parse::<i64>().is_ok(),
+        };
+        if !is_valid_type {
+            let kind = match spec.kind {
+                SlashArgType::String => "a string",
+                SlashArgType::Number => "a number",
+                SlashArgType::Integer => "an integer",
+            };
+            return Err(format!(
+                "`{}` must be {}, got `{}`",
+                self.name(),
+                kind,
+                value
+            ));
+        }
+        if let Some(ref values) = spec.values {
+            if !values.iter().any(|x| x == value) {
+                return Err(format!(
+                    "`{}` must be one of {}, got `{}`",
+                    self.name(),
+                    values.join(", "),
+                    value
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Define word which is just a shortcut for series of other word,
 /// e.g. `sin_osc` could be expanded as `phasor circle sin`.
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct CompoundWord {
     /// How should the word be expanded.
-    /// TODO Consider support for slash args templates.
+    /// `$1`..`$9` are replaced with slash args the word is constructed with,
+    /// e.g. `echo/2` with expansion `feedback/$1` is expanded as `feedback/2`.
     pub expansion: String,
+    /// Values of slash args which are omitted or empty, e.g. `echo` or `echo//0.5`.
+    #[serde(default)]
+    pub defaults: Vec<String>,
 }
 
 #[derive(Deserialize)]
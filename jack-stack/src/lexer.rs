@@ -0,0 +1,314 @@
+//! # Lexer
+//!
+//! Split stack language source into tokens.
+//!
+//! Tokens are separated by whitespace. Token is a word optionally followed by slash args,
+//! e.g. `delay/60/hermite`. Slash arg could be a string literal to include whitespace, slashes and
+//! other special characters, e.g. `sampler/"my samples/kick.wav"`. String literals support `\"`,
+//! `\\`, `\n` and `\t` escapes.
+//!
+//! Numbers become `constant/N` tokens. Besides the usual decimal forms like `440`, `-0.5`, `.25`
+//! and `1e3`, numbers could have `_` separators (`48_000`) and be written in hex (`0xff`) or
+//! binary (`0b1010`).
+//!
+//! `( ... )` and `\ ...` up to the end of line are comments. Both `(` and `\` must be standalone
+//! tokens to start a comment, so words like `(foo` or `\bar` are fine.
+use std::fmt;
+
+#[derive(Clone, Debug)]
+pub struct Token {
+    pub word: String,
+    pub args: Vec<String>,
+    /// Byte offsets of the token in the source.
+    pub start: usize,
+    pub end: usize,
+    /// Position of the token in the source, both start from 1.
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Token {
+    /// Create token which doesn't come from the source.
+    pub fn new(word: &str, args: &[String]) -> Self {
+        Token {
+            word: word.to_string(),
+            args: args.to_vec(),
+            start: 0,
+            end: 0,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    /// Whether token is the given bare word without slash args.
+    pub fn is(&self, word: &str) -> bool {
+        self.word == word && self.args.is_empty()
+    }
+}
+
+/// Format token back into the source form, quoting slash args when required.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.word)?;
+        for arg in &self.args {
+            write!(f, "/{}", quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+/// Quote slash arg value if it can't be written as is.
+pub fn quote(value: &str) -> String {
+    let is_bare = !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '/' || c == '"' || c == '\\');
+    if is_bare {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Error with position in the source.
+#[derive(Debug)]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+    Lexer::new(source).tokenize()
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    /// Byte offset of the current char.
+    position: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Lexer {
+            source,
+            position: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, Error> {
+        let mut tokens = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some('(') if self.is_standalone(1) => self.skip_comment(')')?,
+                Some('\\') if self.is_standalone(1) => self.skip_comment('\n')?,
+                Some(_) => tokens.push(self.token()?),
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn token(&mut self) -> Result<Token, Error> {
+        let (start, line, column) = (self.position, self.line, self.column);
+        let word = match self.peek() {
+            Some('"') => return Err(self.error("String literal could be only a slash arg.")),
+            _ => self.bare(),
+        };
+        let mut args = Vec::new();
+        while let Some('/') = self.peek() {
+            self.next();
+            let arg = match self.peek() {
+                Some('"') => self.string()?,
+                _ => self.bare(),
+            };
+            args.push(arg);
+        }
+        match self.peek() {
+            Some(c) if !c.is_whitespace() => {
+                return Err(self.error(&format!("Unexpected `{}` after string literal.", c)))
+            }
+            _ => {}
+        }
+        let (word, args) = match number(&word) {
+            Some(value) if args.is_empty() => ("constant".to_string(), vec![value.to_string()]),
+            _ => (word, args),
+        };
+        Ok(Token {
+            word,
+            args,
+            start,
+            end: self.position,
+            line,
+            column,
+        })
+    }
+
+    /// Read chars up to whitespace or slash.
+    fn bare(&mut self) -> String {
+        let mut bare = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '/' {
+                break;
+            }
+            bare.push(c);
+            self.next();
+        }
+        bare
+    }
+
+    /// Read string literal, current char must be an opening quote.
+    fn string(&mut self) -> Result<String, Error> {
+        let (line, column) = (self.line, self.column);
+        self.next();
+        let mut string = String::new();
+        loop {
+            let escape = self.error("");
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.next() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some(c) => {
+                        return Err(Error {
+                            message: format!("Unknown escape `\\{}`.", c),
+                            ..escape
+                        })
+                    }
+                    None => break,
+                },
+                Some(c) => string.push(c),
+                None => break,
+            }
+        }
+        Err(Error {
+            line,
+            column,
+            message: "String literal is missing closing `\"`.".to_string(),
+        })
+    }
+
+    /// Skip comment up to the `end` char inclusive, current char must be an opening one.
+    fn skip_comment(&mut self, end: char) -> Result<(), Error> {
+        let (line, column) = (self.line, self.column);
+        self.next();
+        while let Some(c) = self.next() {
+            if c == end {
+                return Ok(());
+            }
+        }
+        match end {
+            '\n' => Ok(()),
+            _ => Err(Error {
+                line,
+                column,
+                message: format!("Comment is missing closing `{}`.", end),
+            }),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.next();
+        }
+    }
+
+    /// Whether char at `offset` bytes after the current one is whitespace or end of source.
+    fn is_standalone(&self, offset: usize) -> bool {
+        self.source[self.position + offset..]
+            .chars()
+            .next()
+            .map(|c| c.is_whitespace())
+            .unwrap_or(true)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error {
+            line: self.line,
+            column: self.column,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Parse numeric literal. Words like `inf` or `nan` are not numbers.
+fn number(word: &str) -> Option<f64> {
+    let (sign, unsigned) = match word.chars().next() {
+        Some('-') => (-1.0, &word[1..]),
+        Some('+') => (1.0, &word[1..]),
+        _ => (1.0, word),
+    };
+    let (radix, digits) = match unsigned.get(..2) {
+        Some("0x") | Some("0X") => (16, &unsigned[2..]),
+        Some("0b") | Some("0B") => (2, &unsigned[2..]),
+        _ => (10, unsigned),
+    };
+    // Sign goes before the radix prefix only, e.g. `0x-5` is not a number.
+    if digits.starts_with(&['+', '-'][..]) {
+        return None;
+    }
+    // Separators are allowed only between digits, so that words like `_1` are not numbers.
+    let chars = digits.chars().collect::<Vec<_>>();
+    let is_separated = (0..chars.len()).filter(|&i| chars[i] == '_').all(|i| {
+        i > 0 && i + 1 < chars.len() && chars[i - 1].is_digit(radix) && chars[i + 1].is_digit(radix)
+    });
+    if !is_separated {
+        return None;
+    }
+    let digits = digits.replace('_', "");
+    if radix != 10 {
+        return i64::from_str_radix(&digits, radix)
+            .ok()
+            .map(|x| sign * x as f64);
+    }
+    let starts_with_digit = digits
+        .trim_start_matches('.')
+        .starts_with(|c: char| c.is_ascii_digit());
+    if !starts_with_digit {
+        return None;
+    }
+    digits.parse::<f64>().ok().map(|x| sign * x)
+}
@@ -5,7 +5,6 @@
 #[macro_use]
 extern crate clap;
 extern crate fnv;
-extern crate itertools;
 extern crate jack;
 extern crate regex;
 extern crate rosc;
@@ -17,10 +16,12 @@ extern crate slog;
 extern crate sloggers;
 #[macro_use]
 extern crate slog_scope;
+extern crate synth_modules;
 extern crate toml;
 
 mod config;
 mod gatekeeper;
+mod lexer;
 mod manager;
 mod module;
 mod stack;
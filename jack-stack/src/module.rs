@@ -2,9 +2,13 @@
 //!
 //! Structures and routines dedicated to managing module instances.
 use config::PrimitiveWord;
-use itertools::Itertools;
 use manager::Manager;
 use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+/// How long to wait for the module's client to become active before giving up.
+/// Generous as some modules load files before registering ports.
+const SPAWN_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct Module {
     /// JACK client name which corresponds to the module instance.
@@ -26,55 +30,126 @@ impl Drop for Module {
 
 impl Module {
     /// Spawn a new module process and wait until its client is active.
-    /// Return None if starting process failed.
+    /// `inputs` are names of module's input ports, usually the ones from definition.
+    /// `slash_args` are command arguments resolved by `slash_args`.
+    /// Return error if starting process failed, or it exited or timed out before becoming active.
     pub fn spawn(
         manager: &Manager,
         definition: &PrimitiveWord,
         name: &str,
-        slash_args_values: &[&str],
-    ) -> Option<Self> {
+        inputs: &[String],
+        slash_args: &[String],
+    ) -> Result<Self, String> {
         // extra_args are passed unconditionally.
         let mut args = definition.extra_args.as_ref().cloned().unwrap_or_default();
-        // TODO Support passing slash args as positional.
-        if let Some(ref slash_args) = definition.slash_args {
-            if slash_args.len() < slash_args_values.len() {
-                warn!("Extra slash args values will be ignored.");
-            }
-            let slash_args = slash_args.iter().cloned();
-            let slash_args_values = slash_args_values.iter().map(|s| s.to_string());
-            args.extend(slash_args.interleave(slash_args_values));
-        }
+        args.extend_from_slice(slash_args);
         // Set module's client name so jack-stack will be able to manipulate module's ports.
         // NOTE This relies on the assumption that module sets USE_EXACT_NAME and will fail if name
         // is already taken.
         args.push(definition.name_arg.to_owned());
         args.push(name.to_string());
 
-        let process = Command::new(definition.cmd.to_owned()).args(args).spawn();
-        if process.is_err() {
-            return None;
-        }
-        let process = process.unwrap();
+        let process = Command::new(definition.cmd.to_owned())
+            .args(args)
+            .spawn()
+            .map_err(|e| format!("failed to run `{}`: {}", definition.cmd, e))?;
+        // Module is constructed right away so the process is killed on drop if it doesn't get
+        // ready.
+        let mut module = Module {
+            name: name.to_string(),
+            process,
+        };
         // JACK doesn't provide any good way to ensure that specific client is active
         // (please let me know if it does and I just don't know).
         // To wait for the module's client being ready code below:
         // * polls server for client ports to be registered;
         // * then polls one of module's output ports to be connectable.
         // 1 ms timeout is completely made up.
-        let expected_ports_count = definition.inputs.len() + definition.outputs.len();
+        let deadline = Instant::now() + SPAWN_TIMEOUT;
+        let expected_ports_count = inputs.len() + definition.outputs.len();
         let ports_regex = format!("^{}:.+$", regex::escape(name));
-        while expected_ports_count > manager.count_ports(&ports_regex) {
-            std::thread::sleep(std::time::Duration::from_millis(1));
-        }
+        module.wait(deadline, || {
+            expected_ports_count <= manager.count_ports(&ports_regex)
+        })?;
         if let Some(port) = definition.outputs.first() {
             let port = format!("{}:{}", name, port);
-            while !manager.output_port_is_ready(&port) {
-                std::thread::sleep(std::time::Duration::from_millis(1));
+            module.wait(deadline, || manager.output_port_is_ready(&port))?;
+        }
+        Ok(module)
+    }
+
+    /// Poll until `is_ready`, fail if the process exits or `deadline` passes first.
+    fn wait<F>(&mut self, deadline: Instant, is_ready: F) -> Result<(), String>
+    where
+        F: Fn() -> bool,
+    {
+        while !is_ready() {
+            match self.process.try_wait() {
+                Ok(Some(status)) => return Err(format!("module exited with {}", status)),
+                Ok(None) => {}
+                Err(e) => return Err(format!("failed to check module process: {}", e)),
+            }
+            if Instant::now() > deadline {
+                return Err(format!(
+                    "module is not ready after {} s",
+                    SPAWN_TIMEOUT.as_secs()
+                ));
             }
+            std::thread::sleep(Duration::from_millis(1));
         }
-        Some(Module {
-            name: name.to_string(),
-            process,
-        })
+        Ok(())
+    }
+}
+
+/// Match slash args values to the word definition and turn them into command arguments.
+/// Value is either positional, or named like `name=value` when `name` is one of the word's slash
+/// args. Empty values are treated as omitted, so `word//x` sets only the second arg.
+/// Validate values against definition, so misconfigured word is reported before spawning a module.
+pub fn slash_args(definition: &PrimitiveWord, values: &[&str]) -> Result<Vec<String>, String> {
+    let specs = match definition.slash_args {
+        Some(ref specs) => specs,
+        None if values.iter().all(|x| x.is_empty()) => return Ok(Vec::new()),
+        None => return Err("word doesn't accept slash args".to_string()),
+    };
+    let mut assigned: Vec<Option<&str>> = vec![None; specs.len()];
+    for (position, value) in values.iter().enumerate() {
+        let named = value.find('=').and_then(|i| {
+            let name = &value[..i];
+            specs
+                .iter()
+                .position(|spec| spec.name() == name)
+                .map(|idx| (idx, &value[i + 1..]))
+        });
+        let (idx, value) = match named {
+            Some(named) => named,
+            None if position < specs.len() => (position, *value),
+            None => {
+                return Err(format!(
+                    "too many slash args, at most {} expected",
+                    specs.len()
+                ))
+            }
+        };
+        if value.is_empty() {
+            continue;
+        }
+        if assigned[idx].is_some() {
+            return Err(format!("`{}` is set more than once", specs[idx].name()));
+        }
+        assigned[idx] = Some(value);
+    }
+    let mut args = Vec::new();
+    for (spec, value) in specs.iter().zip(assigned) {
+        let value = match value.or_else(|| spec.default()) {
+            Some(value) => value,
+            None if spec.is_required() => return Err(format!("`{}` is required", spec.name())),
+            None => continue,
+        };
+        spec.validate(value)?;
+        if let Some(flag) = spec.flag() {
+            args.push(flag.to_string());
+        }
+        args.push(value.to_string());
     }
+    Ok(args)
 }
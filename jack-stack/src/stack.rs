@@ -2,10 +2,57 @@
 //!
 //! Manage JACK clients and connections with style using a simple stack-based language.
 
-use config::{Config, WordDefinition};
-use fnv::FnvHashSet;
+use config::{CompoundWord, Config, WordDefinition};
+use fnv::{FnvHashMap, FnvHashSet};
+use lexer::{self, Token};
 use manager::Manager;
-use module::Module;
+use module::{self, Module};
+use std::slice;
+use synth_modules::expr::Expr;
+
+/// Max length of the JACK client name derived from the token, without `_idx` suffix.
+const MAX_NAME_LENGTH: usize = 32;
+
+/// How deep compound words could expand into each other.
+/// Protects from infinite recursion in words referring to themselves.
+const MAX_EXPANSION_DEPTH: usize = 256;
+
+/// Words handled by the stack itself or having reserved semantics, they can't be (re)defined in
+/// session.
+const RESERVED_WORDS: &[&str] = &[
+    ":",
+    ";",
+    "[",
+    "]",
+    "call",
+    "times",
+    "times-with-index",
+    "each",
+    "bi",
+    "keep",
+    "dip",
+    "clear",
+    "pop",
+    "drop",
+    "dup",
+    "2dup",
+    "over",
+    "pick",
+    "nip",
+    "tuck",
+    "swap",
+    "2swap",
+    "rot",
+    "-rot",
+    "roll",
+    "depth",
+    "bundle",
+    "split",
+    "mixdown",
+    "forget",
+    "unset",
+    "constant",
+];
 
 pub struct Stack {
     /// Track module's inputs, immediate and transitive.
@@ -18,19 +65,68 @@ pub struct Stack {
     /// and in practice not that wasteful (even thousands of `None`s accumulating during session
     /// are nothing in comparison with one-minute delay buffer for example).
     modules: Vec<Option<Module>>,
-    /// Stack of ports. Top ones belonging to the same module are connected to the
+    /// Stack of ports, bundles and quotations. Top ports belonging to the same module are connected to the
     /// system:playback_*. When the new word is evaluated its module inputs are consumed from stack
     /// and its outputs are put back to stack.
     stack: Vec<Element>,
+    /// Session vocabulary of compound words defined at runtime via `: name expansion ;`.
+    /// It takes precedence over the config vocabulary, so config words could be redefined.
+    /// Words are bound late, so redefinition affects every word which expands to the redefined one,
+    /// e.g. redefining `add` changes `+` and `mixdown` as well.
+    definitions: FnvHashMap<String, CompoundWord>,
+    /// Elements stored by `>name` and recalled by `name>`. They persist across evaluations and
+    /// their modules are not collected as garbage until variable is unset or overwritten.
+    variables: FnvHashMap<String, Element>,
+}
+
+#[derive(Clone)]
+enum Element {
+    Port(Port),
+    /// Several ports treated as channels of a single signal, e.g. stereo or voices of a chord.
+    /// Never empty.
+    Bundle(Vec<Port>),
+    /// Tokens to evaluate later by combinators, e.g. `[ 0.5 * ]`.
+    Quotation(String),
+}
+
+impl Element {
+    /// Number of channels of the port or bundle.
+    fn channels(&self) -> usize {
+        match self {
+            Element::Port(_) => 1,
+            Element::Bundle(ports) => ports.len(),
+            Element::Quotation(_) => 0,
+        }
+    }
+
+    /// Port of the given channel, channels of shorter bundles wrap around.
+    /// Must not be called for quotation.
+    fn channel(&self, channel: usize) -> &Port {
+        match self {
+            Element::Port(port) => port,
+            Element::Bundle(ports) => &ports[channel % ports.len()],
+            Element::Quotation(_) => unreachable!(),
+        }
+    }
+
+    fn ports(&self) -> &[Port] {
+        match self {
+            Element::Port(port) => std::slice::from_ref(port),
+            Element::Bundle(ports) => ports,
+            Element::Quotation(_) => &[],
+        }
+    }
 }
 
 /// Stack element corresponding to specific output port.
 #[derive(Clone)]
-struct Element {
+struct Port {
     /// Index of the module to which this port belongs to.
     idx: usize,
     /// Full name of the port (with module name).
     port: String,
+    /// Value of the `constant` module output, used by combinators which take a number.
+    value: Option<f64>,
 }
 
 impl Stack {
@@ -39,137 +135,571 @@ impl Stack {
             connections: Vec::new(),
             modules: Vec::new(),
             stack: Vec::new(),
+            definitions: FnvHashMap::default(),
+            variables: FnvHashMap::default(),
         }
     }
 
     /// Evaluate `s` by tossing the stack, spawning required modules and making required connections.
     /// Then connect top module on the stack to the system playback.
+    /// Evaluation is atomic: on error the stack, variables, definitions and modules are reverted to
+    /// their state before evaluation, and playback is left untouched.
     pub fn eval(&mut self, s: &str, manager: &Manager, config: &Config) {
-        self.eval_internal(s, manager, config);
+        // Modules are only appended during evaluation (GC runs after it), so it's enough to
+        // remember lengths to drop new ones.
+        let modules_len = self.modules.len();
+        let stack = self.stack.clone();
+        let variables = self.variables.clone();
+        let definitions = self.definitions.clone();
+        if let Err(e) = self.eval_internal(s, manager, config, 0) {
+            error!("{} Evaluation is rolled back.", e);
+            // Dropping modules kills their processes, and JACK removes their connections.
+            self.modules.truncate(modules_len);
+            self.connections.truncate(modules_len);
+            self.stack = stack;
+            self.variables = variables;
+            self.definitions = definitions;
+            return;
+        }
         self.reset_system_playback(manager);
         self.collect_garbage();
     }
 
     /// Evaluate `s` by tossing the stack, spawning required modules and making required connections.
-    fn eval_internal(&mut self, s: &str, manager: &Manager, config: &Config) {
-        for token in s.split_whitespace() {
+    /// `depth` is how deep we are in compound words expansion.
+    /// Evaluation stops at the first error, `eval` takes care of rolling back the rest.
+    fn eval_internal(
+        &mut self,
+        s: &str,
+        manager: &Manager,
+        config: &Config,
+        depth: usize,
+    ) -> Result<(), String> {
+        if depth > MAX_EXPANSION_DEPTH {
+            return Err("Words expand too deep, is there a recursive definition?".to_string());
+        }
+        let tokens = lexer::tokenize(s).map_err(|e| e.to_string())?;
+        let mut tokens = tokens.iter();
+        while let Some(token) = tokens.next() {
             debug!("Token: {}", token);
-            let mut token = token.to_string();
-            // `constant` word can have custom definition, but its semantics are reserved.
-            if token.parse::<f64>().is_ok() {
-                token = format!("constant/{}", token);
-            }
-            let args = token.split('/').collect::<Vec<_>>();
-            let word = args[0];
-            match word {
-                "clear" => {
-                    self.stack.clear();
-                }
-                // a -> ()
-                "pop" => {
-                    self.stack.pop();
-                }
-                // a -> a a
-                "dup" => {
-                    if let Some(top_element) = self.stack.last().cloned() {
-                        self.stack.push(top_element.clone());
+            // Positions in nested evaluations are relative to quotation or expansion, so only
+            // top-level ones are reported.
+            self.eval_token(token, &mut tokens, s, manager, config, depth)
+                .map_err(|e| match depth {
+                    0 => format!("{}:{}: {}", token.line, token.column, e),
+                    _ => e,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Evaluate single token, words like `:` and `[` consume following tokens as well.
+    /// `s` is the source `tokens` come from.
+    fn eval_token(
+        &mut self,
+        token: &Token,
+        tokens: &mut slice::Iter<Token>,
+        s: &str,
+        manager: &Manager,
+        config: &Config,
+        depth: usize,
+    ) -> Result<(), String> {
+        let word = token.word.as_str();
+        // a -> (), store `a` into variable: `>lfo`
+        if let Some(name) = word.strip_prefix('>').filter(|x| is_variable_name(x)) {
+            let element = self
+                .stack
+                .pop()
+                .ok_or_else(|| format!("`{}` requires an element on the stack.", word))?;
+            self.variables.insert(name.to_string(), element);
+            return Ok(());
+        }
+        // () -> a, recall `a` from variable: `lfo>`
+        if let Some(name) = word.strip_suffix('>').filter(|x| is_variable_name(x)) {
+            let element = self
+                .variables
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("Variable `{}` is not set.", name))?;
+            self.stack.push(element);
+            return Ok(());
+        }
+        match word {
+            // Define compound word in the session vocabulary: `: name expansion ;`
+            // Slash args of the name are defaults for the expansion template:
+            // `: echo/2 feedback/$1 ;`
+            ":" => {
+                let head = match tokens.next() {
+                    Some(head) => head,
+                    None => return Err("Definition is missing a name.".to_string()),
+                };
+                // Numbers are lexed as `constant`, which is reserved.
+                let name = head.word.as_str();
+                if name.is_empty() || RESERVED_WORDS.contains(&name) {
+                    return Err(format!("`{}` can't be used as a word name.", head));
+                }
+                // Such words would be taken for storing or recalling a variable.
+                let is_variable_syntax =
+                    match name.strip_prefix('>').or_else(|| name.strip_suffix('>')) {
+                        Some(variable) => is_variable_name(variable),
+                        None => false,
+                    };
+                if is_variable_syntax {
+                    return Err(format!(
+                        "`{}` can't be used as a word name as it clashes with variable syntax.",
+                        name
+                    ));
+                }
+                let end = loop {
+                    match tokens.next() {
+                        Some(t) if t.is(";") => break t,
+                        Some(t) if t.is(":") => {
+                            return Err(format!("Definition of `{}` can't be nested.", name))
+                        }
+                        Some(_) => {}
+                        None => return Err(format!("Definition of `{}` is missing `;`.", name)),
                     }
+                };
+                if self.definitions.contains_key(name) {
+                    info!("Redefining `{}`.", name);
+                } else if config.words.contains_key(name) {
+                    warn!(
+                        "Redefining vocabulary word `{}`, words expanding to it are affected too.",
+                        name
+                    );
                 }
-                // a b -> b a
-                "swap" => {
-                    let len = self.stack.len();
-                    if len < 2 {
-                        warn!("Not enough minerals.");
-                        return;
+                // Keep the source as is, with string literals and comments.
+                let expansion = s[head.end..end.start].trim().to_string();
+                let defaults = head.args.clone();
+                self.definitions.insert(
+                    name.to_string(),
+                    CompoundWord {
+                        expansion,
+                        defaults,
+                    },
+                );
+            }
+            ";" => return Err("Unexpected `;` outside of definition.".to_string()),
+            // Put quotation onto the stack: `[ 0.5 * ]`, quotations could be nested.
+            "[" => {
+                let mut nesting = 0;
+                let end = loop {
+                    match tokens.next() {
+                        Some(t) if t.is("]") && nesting == 0 => break t,
+                        Some(t) if t.is("[") => nesting += 1,
+                        Some(t) if t.is("]") => nesting -= 1,
+                        Some(_) => {}
+                        None => return Err("Quotation is missing `]`.".to_string()),
                     }
-                    self.stack.swap(len - 2, len - 1);
-                }
-                // a b c -> b c a
-                "rot" => {
-                    let len = self.stack.len();
-                    if len < 3 {
-                        warn!("You require more Vespene gas.");
-                        return;
+                };
+                let quotation = s[token.end..end.start].trim().to_string();
+                self.stack.push(Element::Quotation(quotation));
+            }
+            "]" => return Err("Unexpected `]` outside of quotation.".to_string()),
+            // a_1 ... a_n -> {a_1 ... a_n}, bundles are flattened
+            "bundle" => {
+                let n = numeric_arg(token, None)?;
+                if n == 0 {
+                    return Err("Bundle can't be empty.".to_string());
+                }
+                self.require(&token.to_string(), n)?;
+                let len = self.stack.len();
+                if self.stack[len - n..]
+                    .iter()
+                    .any(|e| matches!(e, Element::Quotation(_)))
+                {
+                    return Err(format!("`{}` can't bundle quotations.", token));
+                }
+                let ports = self
+                    .stack
+                    .split_off(len - n)
+                    .iter()
+                    .flat_map(|e| e.ports().to_vec())
+                    .collect();
+                self.stack.push(Element::Bundle(ports));
+            }
+            // {a_1 ... a_n} -> a_1 ... a_n
+            "split" => match self.stack.pop() {
+                Some(Element::Bundle(ports)) => {
+                    self.stack.extend(ports.into_iter().map(Element::Port))
+                }
+                Some(element) => self.stack.push(element),
+                None => return Err("`split` requires a bundle on the stack.".to_string()),
+            },
+            // {a_1 ... a_n} -> a_1 + ... + a_n
+            "mixdown" => match self.stack.pop() {
+                Some(Element::Bundle(ports)) => {
+                    let n = ports.len();
+                    self.stack.extend(ports.into_iter().map(Element::Port));
+                    for _ in 1..n {
+                        self.eval_custom_word(&Token::new("add", &[]), manager, config, depth)?;
                     }
-                    self.stack.swap(len - 2, len - 1);
-                    self.stack.swap(len - 3, len - 1);
                 }
-                _ => self.eval_custom_word(&token, manager, config),
+                Some(element) => self.stack.push(element),
+                None => return Err("`mixdown` requires a bundle on the stack.".to_string()),
+            },
+            // q -> (evaluated q)
+            "call" => {
+                let quotation = self.pop_quotation(word)?;
+                self.eval_internal(&quotation, manager, config, depth + 1)?;
+            }
+            // q n -> (q evaluated n times)
+            "times" => {
+                let n = self.pop_count(word)?;
+                let quotation = self.pop_quotation(word)?;
+                for _ in 0..n {
+                    self.eval_internal(&quotation, manager, config, depth + 1)?;
+                }
+            }
+            // q n -> (0 q evaluated) ... (n-1 q evaluated)
+            "times-with-index" => {
+                let n = self.pop_count(word)?;
+                let quotation = self.pop_quotation(word)?;
+                for i in 0..n {
+                    let token = Token::new("constant", &[i.to_string()]);
+                    self.eval_custom_word(&token, manager, config, depth)?;
+                    self.eval_internal(&quotation, manager, config, depth + 1)?;
+                }
+            }
+            // a_1 ... a_n q -> (a_1 q evaluated) ... (a_n q evaluated)
+            "each" => {
+                let quotation = self.pop_quotation(word)?;
+                let elements = self.stack.split_off(0);
+                for element in elements {
+                    self.stack.push(element);
+                    self.eval_internal(&quotation, manager, config, depth + 1)?;
+                }
+            }
+            // x p q -> (x p evaluated) (x q evaluated)
+            "bi" => {
+                let q = self.pop_quotation(word)?;
+                let p = self.pop_quotation(word)?;
+                self.require(word, 1)?;
+                // Ok to unwrap as we checked stack len.
+                let x = self.stack.last().cloned().unwrap();
+                self.eval_internal(&p, manager, config, depth + 1)?;
+                self.stack.push(x);
+                self.eval_internal(&q, manager, config, depth + 1)?;
+            }
+            // x q -> (x q evaluated) x
+            "keep" => {
+                let quotation = self.pop_quotation(word)?;
+                self.require(word, 1)?;
+                // Ok to unwrap as we checked stack len.
+                let x = self.stack.last().cloned().unwrap();
+                self.eval_internal(&quotation, manager, config, depth + 1)?;
+                self.stack.push(x);
+            }
+            // x q -> (q evaluated) x
+            "dip" => {
+                let quotation = self.pop_quotation(word)?;
+                self.require(word, 1)?;
+                // Ok to unwrap as we checked stack len.
+                let x = self.stack.pop().unwrap();
+                self.eval_internal(&quotation, manager, config, depth + 1)?;
+                self.stack.push(x);
+            }
+            // Remove words from the session vocabulary: `forget/name1/name2`
+            "forget" => {
+                for name in &token.args {
+                    if self.definitions.remove(name).is_none() {
+                        warn!("Word `{}` is not defined in session.", name);
+                    }
+                }
+            }
+            // Remove variables: `unset/name1/name2`
+            "unset" => {
+                for name in &token.args {
+                    if self.variables.remove(name).is_none() {
+                        warn!("Variable `{}` is not set.", name);
+                    }
+                }
+            }
+            "clear" => {
+                self.stack.clear();
+            }
+            // a -> ()
+            "pop" => {
+                self.require(word, 1)?;
+                self.stack.pop();
             }
+            // a_n ... a_1 -> (), `drop` is `drop/1`
+            "drop" => {
+                let n = numeric_arg(token, Some(1))?;
+                self.require(&token.to_string(), n)?;
+                let len = self.stack.len();
+                self.stack.truncate(len - n);
+            }
+            // a -> a a
+            "dup" => {
+                self.require(word, 1)?;
+                self.pick(0);
+            }
+            // a b -> a b a b
+            "2dup" => {
+                self.require(word, 2)?;
+                let len = self.stack.len();
+                self.stack.extend_from_within(len - 2..);
+            }
+            // a b -> a b a
+            "over" => {
+                self.require(word, 2)?;
+                self.pick(1);
+            }
+            // a_n ... a_0 -> a_n ... a_0 a_n, `pick/0` is `dup`, `pick/1` is `over`
+            "pick" => {
+                let n = numeric_arg(token, None)?;
+                self.require(&token.to_string(), n + 1)?;
+                self.pick(n);
+            }
+            // a b -> b
+            "nip" => {
+                self.require(word, 2)?;
+                let len = self.stack.len();
+                self.stack.remove(len - 2);
+            }
+            // a b -> b a b
+            "tuck" => {
+                self.require(word, 2)?;
+                let len = self.stack.len();
+                let top_element = self.stack[len - 1].clone();
+                self.stack.insert(len - 2, top_element);
+            }
+            // a b -> b a
+            "swap" => {
+                self.require(word, 2)?;
+                self.roll(1);
+            }
+            // a b c d -> c d a b
+            "2swap" => {
+                self.require(word, 4)?;
+                let len = self.stack.len();
+                self.stack[len - 4..].rotate_left(2);
+            }
+            // a b c -> b c a
+            "rot" => {
+                self.require(word, 3)?;
+                self.roll(2);
+            }
+            // a b c -> c a b
+            "-rot" => {
+                self.require(word, 3)?;
+                let len = self.stack.len();
+                self.stack[len - 3..].rotate_right(1);
+            }
+            // a_n ... a_0 -> a_n-1 ... a_0 a_n, `roll/1` is `swap`, `roll/2` is `rot`
+            "roll" => {
+                let n = numeric_arg(token, None)?;
+                self.require(&token.to_string(), n + 1)?;
+                self.roll(n);
+            }
+            // () -> n, where n is a constant of the stack depth before evaluation
+            "depth" => {
+                let token = Token::new("constant", &[self.stack.len().to_string()]);
+                self.eval_custom_word(&token, manager, config, depth)?;
+            }
+            _ => self.eval_custom_word(token, manager, config, depth)?,
         }
+        Ok(())
+    }
+
+    /// Check that stack has at least `n` elements to evaluate `word`.
+    fn require(&self, word: &str, n: usize) -> Result<(), String> {
+        let len = self.stack.len();
+        if len < n {
+            return Err(format!(
+                "`{}` requires {} elements on the stack, but there are only {}.",
+                word, n, len
+            ));
+        }
+        Ok(())
+    }
+
+    /// Pop quotation from the top of the stack to evaluate `word`.
+    fn pop_quotation(&mut self, word: &str) -> Result<String, String> {
+        match self.stack.pop() {
+            Some(Element::Quotation(quotation)) => Ok(quotation),
+            Some(element) => {
+                self.stack.push(element);
+                Err(format!(
+                    "`{}` expects a quotation on the top of the stack.",
+                    word
+                ))
+            }
+            None => Err(format!("`{}` requires a quotation on the stack.", word)),
+        }
+    }
+
+    /// Pop non-negative integer literal from the top of the stack to evaluate `word`.
+    fn pop_count(&mut self, word: &str) -> Result<usize, String> {
+        match self.stack.last() {
+            Some(Element::Port(Port {
+                value: Some(value), ..
+            })) if *value >= 0.0 && value.fract() == 0.0 => {
+                let count = *value as usize;
+                self.stack.pop();
+                Ok(count)
+            }
+            _ => Err(format!(
+                "`{}` expects a non-negative integer literal on the top of the stack.",
+                word
+            )),
+        }
+    }
+
+    /// Copy n-th element from the top (0 is the top one) to the top.
+    fn pick(&mut self, n: usize) {
+        let len = self.stack.len();
+        let element = self.stack[len - 1 - n].clone();
+        self.stack.push(element);
+    }
+
+    /// Move n-th element from the top (0 is the top one) to the top.
+    fn roll(&mut self, n: usize) {
+        let len = self.stack.len();
+        let element = self.stack.remove(len - 1 - n);
+        self.stack.push(element);
     }
 
     /// Evaluate token by spawning required module and making required connections for primitive
     /// word, expand and evaluate compound one.
-    fn eval_custom_word(&mut self, token: &str, manager: &Manager, config: &Config) {
-        let args = token.split('/').collect::<Vec<_>>();
-        let word = args[0];
+    fn eval_custom_word(
+        &mut self,
+        token: &Token,
+        manager: &Manager,
+        config: &Config,
+        depth: usize,
+    ) -> Result<(), String> {
+        let word = token.word.as_str();
+        let args = token.args.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+        if let Some(definition) = self.definitions.get(word) {
+            let expansion = expand(definition, &args)
+                .map_err(|e| format!("Failed to expand `{}`: {}.", token, e))?;
+            return self.eval_internal(&expansion, manager, config, depth + 1);
+        }
         match config.words.get(word) {
-            Some(definition) => match definition {
-                WordDefinition::Primitive(definition) => {
-                    if self.stack.len() < definition.inputs.len() {
-                        error!("Not enough inputs on the stack.");
-                        return;
-                    }
+            Some(WordDefinition::Primitive(definition)) => {
+                let mut inputs = definition.inputs.clone();
+                let mut slash_args = args.clone();
+                let mut name = client_name(token);
+                let expression = args.join("/");
+                if definition.expression_inputs {
+                    let expr = Expr::parse(&expression).map_err(|e| {
+                        format!("Failed to parse expression `{}`: {}.", expression, e)
+                    })?;
+                    inputs = expr.variables().to_vec();
+                    slash_args = vec![&expression];
+                    // Expression is likely to contain characters which are not welcome in
+                    // JACK client name.
+                    name = word.to_string();
+                }
+                let token = token.to_string();
+                self.require(&token, inputs.len())?;
+                let len = self.stack.len();
+                if let Some(Element::Quotation(quotation)) = self.stack[len - inputs.len()..]
+                    .iter()
+                    .find(|e| matches!(e, Element::Quotation(_)))
+                {
+                    return Err(format!(
+                        "`{}` expects ports as inputs, but got quotation `[ {} ]`.",
+                        token, quotation
+                    ));
+                }
+                let slash_args = module::slash_args(definition, &slash_args)
+                    .map_err(|e| format!("Invalid slash args of `{}`: {}.", token, e))?;
+                // Elements are taken in the same order as inputs, so when word A has module
+                // outputs defined as ["a", "b"] and word X has module inputs defined as ["x", "y"],
+                // evaluating `A X` connects "a" to "x" and "b" to "y".
+                let elements = self.stack.split_off(len - inputs.len());
+                // When any of inputs is a bundle, module is spawned for every its channel, and
+                // outputs become bundles as well. Shorter bundles and single ports are repeated.
+                let is_expanded = elements.iter().any(|e| matches!(e, Element::Bundle(_)));
+                let channels = elements.iter().map(Element::channels).max().unwrap_or(1);
+                // `constant` word can have custom definition, but its semantics are reserved.
+                let value = match word {
+                    "constant" => args.first().and_then(|x| x.parse().ok()),
+                    _ => None,
+                };
+                let mut outputs = vec![Vec::with_capacity(channels); definition.outputs.len()];
+                for channel in 0..channels {
                     let idx = self.modules.len();
-                    let name = format!("{}_{}", token, idx);
-                    let module = Module::spawn(manager, definition, &name, &args[1..]);
-                    if module.is_none() {
-                        error!("Failed to spawn a module.");
-                        return;
-                    }
-                    let module = module.unwrap();
+                    let name = format!("{}_{}", name, idx);
+                    let module = Module::spawn(manager, definition, &name, &inputs, &slash_args);
+                    let module = match module {
+                        Ok(module) => module,
+                        Err(e) => {
+                            self.stack.extend(elements);
+                            return Err(format!(
+                                "Failed to spawn a module for `{}`: {}.",
+                                token, e
+                            ));
+                        }
+                    };
                     self.modules.push(Some(module));
                     let mut connections: FnvHashSet<usize> = FnvHashSet::default();
-                    // Inputs are iterated in the reverse order to support following convention.
-                    // Let word A has module outputs defined as ["a", "b"] and word X has module
-                    // inputs defined as ["x", "y"]. Evaluating word A should put port "a" onto
-                    // the stack first and then port "b". Evaluating word B should connect
-                    // "a" to "x" and "b" to "y". As we pop from stack starting from the end,
-                    // A's outputs appear in the reverse order. To match it, B's inputs must be
-                    // iterated in the reverse order as well.
-                    for input in definition.inputs.iter().rev() {
-                        // Ok to unwrap as we checked stack len against inputs len.
-                        let elem = self.stack.pop().unwrap();
-                        manager
-                            .connect_ports(&elem.port, &format!("{}:{}", name, input))
-                            .expect("Failed to connect ports");
-                        connections.extend(self.connections[elem.idx].as_ref().unwrap());
-                        connections.insert(elem.idx);
+                    for (input, element) in inputs.iter().zip(&elements) {
+                        let port = element.channel(channel);
+                        let input = format!("{}:{}", name, input);
+                        manager.connect_ports(&port.port, &input).map_err(|e| {
+                            format!("Failed to connect `{}` to `{}`: {:?}.", port.port, input, e)
+                        })?;
+                        connections.extend(self.connections[port.idx].as_ref().unwrap());
+                        connections.insert(port.idx);
                     }
                     self.connections.push(Some(connections));
-                    for output in &definition.outputs {
-                        let element = Element {
+                    for (ports, output) in outputs.iter_mut().zip(&definition.outputs) {
+                        ports.push(Port {
                             idx,
                             port: format!("{}:{}", name, output),
-                        };
-                        self.stack.push(element);
+                            value,
+                        });
                     }
                 }
-                WordDefinition::Compound(definition) => {
-                    self.eval_internal(&definition.expansion, manager, config)
+                for mut ports in outputs {
+                    let element = if is_expanded {
+                        Element::Bundle(ports)
+                    } else {
+                        // Ok to unwrap as there is exactly one channel.
+                        Element::Port(ports.pop().unwrap())
+                    };
+                    self.stack.push(element);
                 }
-            },
-            None => {
-                error!("Word `{}` is not defined.", word);
+                Ok(())
+            }
+            Some(WordDefinition::Compound(definition)) => {
+                let expansion = expand(definition, &args)
+                    .map_err(|e| format!("Failed to expand `{}`: {}.", token, e))?;
+                self.eval_internal(&expansion, manager, config, depth + 1)
             }
+            None => Err(format!("Word `{}` is not defined.", word)),
         }
     }
 
     /// Connect ports on the top of the stack belonging to the same module to the system playback.
+    /// Bundle on the top is connected channel by channel.
+    /// Quotation on the top leaves playback as it is.
     fn reset_system_playback(&self, manager: &Manager) {
-        if let Some(top_module_idx) = self.stack.last().and_then(|e| Some(e.idx)) {
-            let outputs = self
-                .stack
-                .iter()
-                .rev()
-                .take_while(|e| e.idx == top_module_idx)
-                .map(|e| e.port.to_owned());
-            manager.reset_outputs(outputs);
+        match self.stack.last() {
+            Some(Element::Bundle(ports)) => {
+                manager.reset_outputs(ports.iter().map(|port| port.port.to_owned()));
+            }
+            Some(Element::Port(top_port)) => {
+                let outputs = self
+                    .stack
+                    .iter()
+                    .rev()
+                    .map_while(|e| match e {
+                        Element::Port(port) if port.idx == top_port.idx => Some(port),
+                        _ => None,
+                    })
+                    .map(|port| port.port.to_owned());
+                manager.reset_outputs(outputs);
+            }
+            Some(Element::Quotation(_)) | None => {}
         }
     }
 
-    /// Drop modules which are not connected to the stack.
+    /// Drop modules which are not connected to the stack or variables.
     fn collect_garbage(&mut self) {
         let all_modules = self
             .modules
@@ -179,9 +709,10 @@ impl Stack {
             .collect::<FnvHashSet<_>>();
 
         let mut stack_modules: FnvHashSet<usize> = FnvHashSet::default();
-        for e in &self.stack {
-            stack_modules.extend(self.connections[e.idx].as_ref().unwrap());
-            stack_modules.insert(e.idx);
+        let roots = self.stack.iter().chain(self.variables.values());
+        for port in roots.flat_map(Element::ports) {
+            stack_modules.extend(self.connections[port.idx].as_ref().unwrap());
+            stack_modules.insert(port.idx);
         }
 
         let garbage = &all_modules - &stack_modules;
@@ -194,3 +725,66 @@ impl Stack {
         }
     }
 }
+
+/// Variable name starts with a letter and consists of letters, digits, `-` and `_`,
+/// so that comparison words like `>=` are not mistaken for variables.
+fn is_variable_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// JACK client name for the module spawned by token. Slash args could contain characters which
+/// are not welcome in client name, e.g. string literals, so they are replaced with `_`.
+fn client_name(token: &Token) -> String {
+    let name = token.to_string();
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '.' | '/' => c,
+            _ => '_',
+        })
+        .take(MAX_NAME_LENGTH)
+        .collect()
+}
+
+/// Parse the first slash arg of built-in word as a non-negative integer, use `default` if omitted.
+fn numeric_arg(token: &Token, default: Option<usize>) -> Result<usize, String> {
+    match (token.args.first(), default) {
+        (Some(n), _) => n
+            .parse()
+            .map_err(|_| format!("`{}` expects a non-negative integer slash arg.", token)),
+        (None, Some(default)) => Ok(default),
+        (None, None) => Err(format!(
+            "`{}` requires a numeric slash arg, e.g. `{}/1`.",
+            token.word, token.word
+        )),
+    }
+}
+
+/// Substitute `$1`..`$9` in the compound word expansion with slash args or their defaults.
+/// Values are quoted when required, so `$N` should not be put inside a string literal.
+fn expand(definition: &CompoundWord, args: &[&str]) -> Result<String, String> {
+    let mut expansion = String::with_capacity(definition.expansion.len());
+    let mut chars = definition.expansion.chars().peekable();
+    while let Some(c) = chars.next() {
+        let n = match (c, chars.peek().and_then(|c| c.to_digit(10))) {
+            ('$', Some(n)) if n > 0 => n as usize,
+            _ => {
+                expansion.push(c);
+                continue;
+            }
+        };
+        chars.next();
+        let value = args
+            .get(n - 1)
+            .filter(|x| !x.is_empty())
+            .cloned()
+            .or_else(|| definition.defaults.get(n - 1).map(|x| x.as_str()));
+        match value {
+            Some(value) => expansion.push_str(&lexer::quote(value)),
+            None => return Err(format!("slash arg ${} is required", n)),
+        }
+    }
+    Ok(expansion)
+}
@@ -0,0 +1,62 @@
+//! # Biquad
+//!
+//! Second order IIR band-pass filter (constant 0 dB peak gain) from the Audio EQ Cookbook by
+//! Robert Bristow-Johnson.
+//!
+//! Sources to connect: input, center frequency, Q.
+use sample::Sample;
+use std::f64::consts::PI;
+
+pub struct BPF {
+    /// Feedforward coefficients b0 and b2 (b1 is always 0 for band-pass) and feedback a1, a2,
+    /// all normalized by a0.
+    b0: Sample,
+    b2: Sample,
+    a1: Sample,
+    a2: Sample,
+    x: [Sample; 2],
+    y: [Sample; 2],
+    /// Parameters coefficients were calculated for, to avoid recalculation when they are constant.
+    frequency: Sample,
+    q: Sample,
+    sample_angular_period: Sample,
+}
+
+impl BPF {
+    pub fn new(sample_rate: usize) -> Self {
+        BPF {
+            b0: 0.0,
+            b2: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            x: [0.0; 2],
+            y: [0.0; 2],
+            frequency: 0.0,
+            q: 0.0,
+            sample_angular_period: 2.0 * PI / sample_rate as Sample,
+        }
+    }
+
+    pub fn sample(&mut self, input: Sample, frequency: Sample, q: Sample) -> Sample {
+        if frequency != self.frequency || q != self.q {
+            self.update(frequency, q);
+        }
+        let output =
+            self.b0 * input + self.b2 * self.x[1] - self.a1 * self.y[0] - self.a2 * self.y[1];
+        self.x = [input, self.x[0]];
+        self.y = [output, self.y[0]];
+        output
+    }
+
+    fn update(&mut self, frequency: Sample, q: Sample) {
+        self.frequency = frequency;
+        self.q = q;
+        let w = frequency * self.sample_angular_period;
+        let alpha = w.sin() / (2.0 * q.max(Sample::EPSILON));
+        let a0 = 1.0 + alpha;
+        self.b0 = alpha / a0;
+        self.b2 = -alpha / a0;
+        self.a1 = -2.0 * w.cos() / a0;
+        self.a2 = (1.0 - alpha) / a0;
+    }
+}
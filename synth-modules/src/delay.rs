@@ -1,8 +1,13 @@
 //! # Delay
 //!
 //! Variable signal delay up to maximum period.
+//! Interpolation method to read signal between samples is selected on construction.
 //!
 //! Sources to connect: input to delay, delay time.
+//!
+//! Besides `sample` which writes and reads at once, delay line could be used as a building block
+//! with several read heads: `push` the input and then `tap` it as many times as needed.
+use interpolation::{self, Interpolation};
 use sample::Sample;
 
 pub struct Delay {
@@ -10,14 +15,17 @@ pub struct Delay {
     mask: usize,
     frame_number: usize,
     sample_rate: Sample,
+    interpolation: Interpolation,
+    /// Previous output of the all-pass interpolator.
+    allpass: Sample,
 }
 
 impl Delay {
-    pub fn new(sample_rate: usize, max_delay: Sample) -> Self {
+    pub fn new(sample_rate: usize, max_delay: Sample, interpolation: Interpolation) -> Self {
         let sample_rate = sample_rate as Sample;
-        // +1 because interpolation looks for the next sample
+        // +3 because interpolation looks for up to two next samples and one previous
         // next_power_of_two to trade memory for speed by replacing `mod` with `&`
-        let max_delay_frames = ((sample_rate * max_delay) as usize + 1).next_power_of_two();
+        let max_delay_frames = ((sample_rate * max_delay) as usize + 3).next_power_of_two();
         let mask = max_delay_frames - 1;
         let buffer = vec![0.0; max_delay_frames];
         Delay {
@@ -25,23 +33,57 @@ impl Delay {
             frame_number: 0,
             mask,
             sample_rate,
+            interpolation,
+            allpass: 0.0,
         }
     }
 
     pub fn sample(&mut self, x: Sample, delay: Sample) -> Sample {
-        let z = delay * self.sample_rate;
+        self.push(x);
+        self.tap(delay)
+    }
+
+    /// Write the next input frame.
+    pub fn push(&mut self, x: Sample) {
+        self.buffer[self.frame_number & self.mask] = x;
+        self.frame_number += 1;
+    }
+
+    /// Read signal `delay` seconds before the last pushed frame.
+    pub fn tap(&mut self, delay: Sample) -> Sample {
+        let z = (delay * self.sample_rate).max(0.0);
         let delay = z as usize;
         let k = z.fract();
-        let output = if self.frame_number > delay {
-            let i = self.frame_number - delay;
-            let a = self.buffer[(i - 1) & self.mask];
-            let b = self.buffer[i & self.mask];
-            k * a + (1.0 - k) * b
-        } else {
-            0.0
-        };
-        self.buffer[(self.frame_number & self.mask)] = x;
-        self.frame_number += 1;
-        output
+        match self.interpolation {
+            Interpolation::None => self.frame(delay),
+            Interpolation::Linear => {
+                interpolation::linear(self.frame(delay), self.frame(delay + 1), k)
+            }
+            Interpolation::Hermite => interpolation::hermite(
+                self.frame(delay.saturating_sub(1)),
+                self.frame(delay),
+                self.frame(delay + 1),
+                self.frame(delay + 2),
+                k,
+            ),
+            Interpolation::Lagrange => interpolation::lagrange(
+                self.frame(delay.saturating_sub(1)),
+                self.frame(delay),
+                self.frame(delay + 1),
+                self.frame(delay + 2),
+                k,
+            ),
+            Interpolation::AllPass => {
+                let eta = (1.0 - k) / (1.0 + k);
+                self.allpass = eta * self.frame(delay) + self.frame(delay + 1) - eta * self.allpass;
+                self.allpass
+            }
+        }
+    }
+
+    /// Frame pushed `delay` frames before the last one.
+    /// Buffer is zero-initialized, so reading before the start returns silence.
+    fn frame(&self, delay: usize) -> Sample {
+        self.buffer[self.frame_number.wrapping_sub(delay + 1) & self.mask]
     }
 }
@@ -0,0 +1,51 @@
+//! # Envelope follower
+//!
+//! Track amplitude of the signal to use it as a modulation source.
+//!
+//! Peak mode follows the absolute value of the signal, RMS mode follows the root of its mean square,
+//! which is closer to the perceived loudness. Attack and release are time constants (seconds) of
+//! smoothing applied when the signal is rising and falling respectively.
+//!
+//! Sources to connect: input, attack, release.
+use sample::Sample;
+
+#[derive(Clone, Copy)]
+pub enum Mode {
+    Peak,
+    RMS,
+}
+
+pub struct EnvelopeFollower {
+    mode: Mode,
+    /// Smoothed absolute value in peak mode, smoothed square in RMS mode.
+    envelope: Sample,
+    sample_period: Sample,
+}
+
+impl EnvelopeFollower {
+    pub fn new(sample_rate: usize, mode: Mode) -> Self {
+        EnvelopeFollower {
+            mode,
+            envelope: 0.0,
+            sample_period: (sample_rate as Sample).recip(),
+        }
+    }
+
+    pub fn sample(&mut self, input: Sample, attack: Sample, release: Sample) -> Sample {
+        let x = match self.mode {
+            Mode::Peak => input.abs(),
+            Mode::RMS => input * input,
+        };
+        let time = if x > self.envelope { attack } else { release };
+        let k = if time > 0.0 {
+            (-self.sample_period / time).exp()
+        } else {
+            0.0
+        };
+        self.envelope = x + k * (self.envelope - x);
+        match self.mode {
+            Mode::Peak => self.envelope,
+            Mode::RMS => self.envelope.sqrt(),
+        }
+    }
+}
@@ -0,0 +1,422 @@
+//! # Expression
+//!
+//! Compile arithmetic expression over named variables into an evaluator, so arbitrary pure
+//! transformations could be used without writing dedicated functions for them.
+//!
+//! Expression is parsed once into a postfix program for a tiny stack machine. Evaluating it doesn't
+//! allocate memory and is cheap enough to be done in the audio thread for every sample.
+//!
+//! Supported syntax, from the lowest to the highest precedence:
+//!
+//! * `c ? a : b`                      -- a if c > 0, b otherwise
+//! * `a || b`                         -- logical or
+//! * `a && b`                         -- logical and
+//! * `==`, `!=`, `<`, `>`, `<=`, `>=` -- comparisons
+//! * `+`, `-`                         -- addition and subtraction
+//! * `*`, `/`, `%`                    -- multiplication, division and modulo
+//! * `-a`, `!a`                       -- negation and logical not
+//! * `a ^ b`                          -- power, right associative
+//! * numbers, `pi`, `tau`, variables, parentheses and function calls, e.g. `max(x, 0)`
+//!
+//! Logic follows `pure` conventions: positive is true, results are 1 for true and 0 for false.
+//! Functions are the ones from `pure` available to `Fn1`, `Fn2` and `Fn3` modules.
+//!
+//! Any other identifier is a variable. Variables are sorted alphabetically to define the order of
+//! values passed to `eval`, e.g. `y * sin(x)` expects x first and then y.
+use pure::*;
+use sample::Sample;
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy)]
+enum Op {
+    Constant(Sample),
+    Variable(usize),
+    Fn1(fn(Sample) -> Sample),
+    Fn2(fn(Sample, Sample) -> Sample),
+    Fn3(fn(Sample, Sample, Sample) -> Sample),
+}
+
+pub struct Expr {
+    /// Postfix program.
+    program: Vec<Op>,
+    /// Variables names in the order of values expected by `eval`.
+    variables: Vec<String>,
+    /// Evaluation stack preallocated to the max depth required by program.
+    stack: Vec<Sample>,
+}
+
+impl Expr {
+    /// Parse expression, return error message with position (in chars) if it's malformed.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            program: Vec::new(),
+            variables: Vec::new(),
+        };
+        parser.parse_ternary()?;
+        if let Some(&(_, at)) = parser.tokens.get(parser.position) {
+            return Err(format!("Unexpected token at {}", at));
+        }
+        // Variables are numbered in order of appearance while parsing, renumber them alphabetically.
+        let appearance = parser.variables;
+        let mut variables = appearance.clone();
+        variables.sort();
+        let mut program = parser.program;
+        for op in &mut program {
+            if let Op::Variable(ref mut i) = op {
+                *i = variables.iter().position(|v| *v == appearance[*i]).unwrap();
+            }
+        }
+        let stack = Vec::with_capacity(max_depth(&program));
+        Ok(Expr {
+            program,
+            variables,
+            stack,
+        })
+    }
+
+    /// Names of variables in the order of values expected by `eval`.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// Evaluate expression with the given variables values.
+    /// Missing values are treated as 0.
+    pub fn eval(&mut self, values: &[Sample]) -> Sample {
+        self.stack.clear();
+        for op in &self.program {
+            let x = match *op {
+                Op::Constant(x) => x,
+                Op::Variable(i) => values.get(i).cloned().unwrap_or(0.0),
+                Op::Fn1(f) => {
+                    let x = self.stack.pop().unwrap();
+                    f(x)
+                }
+                Op::Fn2(f) => {
+                    let y = self.stack.pop().unwrap();
+                    let x = self.stack.pop().unwrap();
+                    f(x, y)
+                }
+                Op::Fn3(f) => {
+                    let z = self.stack.pop().unwrap();
+                    let y = self.stack.pop().unwrap();
+                    let x = self.stack.pop().unwrap();
+                    f(x, y, z)
+                }
+            };
+            self.stack.push(x);
+        }
+        self.stack.pop().unwrap_or(0.0)
+    }
+}
+
+/// Calculate how deep the stack grows during program evaluation.
+fn max_depth(program: &[Op]) -> usize {
+    let mut depth: usize = 0;
+    let mut max_depth = 0;
+    for op in program {
+        depth = match *op {
+            Op::Constant(_) | Op::Variable(_) => depth + 1,
+            Op::Fn1(_) => depth,
+            Op::Fn2(_) => depth - 1,
+            Op::Fn3(_) => depth - 2,
+        };
+        max_depth = max_depth.max(depth);
+    }
+    max_depth
+}
+
+#[derive(Clone)]
+enum Token {
+    Number(Sample),
+    Identifier(String),
+    /// Operators and punctuation.
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 20] = [
+    "||", "&&", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "^", "!", "?", ":", "(",
+    ")", ",",
+];
+
+/// Split expression into tokens paired with their positions.
+fn tokenize(s: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Exponent, e.g. 1e-3
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let number = chars[start..i].iter().collect::<String>();
+            let number = number
+                .parse()
+                .map_err(|_| format!("Invalid number `{}` at {}", number, start))?;
+            tokens.push((Token::Number(number), start));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let identifier = chars[start..i].iter().collect();
+            tokens.push((Token::Identifier(identifier), start));
+        } else {
+            let symbol = SYMBOLS.iter().find(|symbol| {
+                symbol
+                    .chars()
+                    .enumerate()
+                    .all(|(j, c)| chars.get(i + j) == Some(&c))
+            });
+            match symbol {
+                Some(symbol) => {
+                    tokens.push((Token::Symbol(symbol), i));
+                    i += symbol.len();
+                }
+                None => return Err(format!("Unexpected character `{}` at {}", c, i)),
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser which emits postfix program.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    program: Vec<Op>,
+    /// Variables in order of appearance.
+    variables: Vec<String>,
+}
+
+type BinaryOperators = [(&'static str, fn(Sample, Sample) -> Sample)];
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    /// Position of the current token for error messages.
+    fn location(&self) -> String {
+        match self.tokens.get(self.position) {
+            Some((_, at)) => format!("at {}", at),
+            None => "at the end".to_string(),
+        }
+    }
+
+    /// Consume the current token if it's the given symbol.
+    fn accept(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) if *s == symbol => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if self.accept(symbol) {
+            Ok(())
+        } else {
+            Err(format!("Expected `{}` {}", symbol, self.location()))
+        }
+    }
+
+    fn parse_ternary(&mut self) -> Result<(), String> {
+        self.parse_binary(0)?;
+        if self.accept("?") {
+            self.parse_ternary()?;
+            self.expect(":")?;
+            self.parse_ternary()?;
+            self.program.push(Op::Fn3(select));
+        }
+        Ok(())
+    }
+
+    /// Parse left associative binary operators of the given precedence level and higher.
+    fn parse_binary(&mut self, level: usize) -> Result<(), String> {
+        const LEVELS: [&BinaryOperators; 5] = [
+            &[("||", or)],
+            &[("&&", and)],
+            &[
+                ("==", eq),
+                ("!=", ne),
+                ("<=", le),
+                (">=", ge),
+                ("<", lt),
+                (">", gt),
+            ],
+            &[("+", add), ("-", sub)],
+            &[("*", mul), ("/", div), ("%", modulo)],
+        ];
+        if level == LEVELS.len() {
+            return self.parse_unary();
+        }
+        self.parse_binary(level + 1)?;
+        'outer: loop {
+            for &(symbol, f) in LEVELS[level] {
+                if self.accept(symbol) {
+                    self.parse_binary(level + 1)?;
+                    self.program.push(Op::Fn2(f));
+                    continue 'outer;
+                }
+            }
+            return Ok(());
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<(), String> {
+        if self.accept("-") {
+            self.parse_unary()?;
+            self.program.push(Op::Fn1(neg));
+        } else if self.accept("!") {
+            self.parse_unary()?;
+            self.program.push(Op::Fn1(not));
+        } else {
+            self.parse_power()?;
+        }
+        Ok(())
+    }
+
+    fn parse_power(&mut self) -> Result<(), String> {
+        self.parse_primary()?;
+        if self.accept("^") {
+            // Right associativity and `2^-x` are supported by recurring to unary.
+            self.parse_unary()?;
+            self.program.push(Op::Fn2(pow));
+        }
+        Ok(())
+    }
+
+    fn parse_primary(&mut self) -> Result<(), String> {
+        let location = self.location();
+        match self.peek().cloned() {
+            Some(Token::Number(x)) => {
+                self.position += 1;
+                self.program.push(Op::Constant(x));
+            }
+            Some(Token::Symbol("(")) => {
+                self.position += 1;
+                self.parse_ternary()?;
+                self.expect(")")?;
+            }
+            Some(Token::Identifier(name)) => {
+                self.position += 1;
+                if self.accept("(") {
+                    let mut arity = 0;
+                    if !self.accept(")") {
+                        loop {
+                            self.parse_ternary()?;
+                            arity += 1;
+                            if !self.accept(",") {
+                                break;
+                            }
+                        }
+                        self.expect(")")?;
+                    }
+                    let op = function(&name, arity).ok_or_else(|| {
+                        format!("Unknown function `{}` of {} arguments {}", name, arity, location)
+                    })?;
+                    self.program.push(op);
+                } else {
+                    let op = match &name as &str {
+                        "pi" => Op::Constant(PI),
+                        "tau" => Op::Constant(2.0 * PI),
+                        _ => {
+                            let i = match self.variables.iter().position(|v| *v == name) {
+                                Some(i) => i,
+                                None => {
+                                    self.variables.push(name);
+                                    self.variables.len() - 1
+                                }
+                            };
+                            Op::Variable(i)
+                        }
+                    };
+                    self.program.push(op);
+                }
+            }
+            _ => return Err(format!("Expected number, variable or `(` {}", location)),
+        }
+        Ok(())
+    }
+}
+
+fn neg(x: Sample) -> Sample {
+    -x
+}
+
+/// Find function by name and arity.
+fn function(name: &str, arity: usize) -> Option<Op> {
+    let op = match (name, arity) {
+        ("sin", 1) => Op::Fn1(sin),
+        ("cos", 1) => Op::Fn1(cos),
+        ("sine", 1) => Op::Fn1(sine),
+        ("cosine", 1) => Op::Fn1(cosine),
+        ("triangle", 1) => Op::Fn1(triangle),
+        ("unit", 1) => Op::Fn1(unit),
+        ("circle", 1) => Op::Fn1(circle),
+        ("cheb2", 1) => Op::Fn1(cheb2),
+        ("cheb3", 1) => Op::Fn1(cheb3),
+        ("cheb4", 1) => Op::Fn1(cheb4),
+        ("cheb5", 1) => Op::Fn1(cheb5),
+        ("cheb6", 1) => Op::Fn1(cheb6),
+        ("abs", 1) => Op::Fn1(abs),
+        ("sign", 1) => Op::Fn1(sign),
+        ("floor", 1) => Op::Fn1(floor),
+        ("ceil", 1) => Op::Fn1(ceil),
+        ("fract", 1) => Op::Fn1(fract),
+        ("exp", 1) => Op::Fn1(exp),
+        ("log", 1) => Op::Fn1(log),
+        ("sqrt", 1) => Op::Fn1(sqrt),
+        ("tanh", 1) => Op::Fn1(tanh),
+        ("not", 1) => Op::Fn1(not),
+        ("mtof", 1) => Op::Fn1(midi_to_hz),
+        ("ftom", 1) => Op::Fn1(hz_to_midi),
+        ("dbamp", 1) => Op::Fn1(db_to_amp),
+        ("ampdb", 1) => Op::Fn1(amp_to_db),
+        ("add", 2) => Op::Fn2(add),
+        ("mul", 2) => Op::Fn2(mul),
+        ("sub", 2) => Op::Fn2(sub),
+        ("div", 2) => Op::Fn2(div),
+        ("rectangle", 2) => Op::Fn2(rectangle),
+        ("mod", 2) => Op::Fn2(modulo),
+        ("pow", 2) => Op::Fn2(pow),
+        ("min", 2) => Op::Fn2(min),
+        ("max", 2) => Op::Fn2(max),
+        ("lt", 2) => Op::Fn2(lt),
+        ("gt", 2) => Op::Fn2(gt),
+        ("le", 2) => Op::Fn2(le),
+        ("ge", 2) => Op::Fn2(ge),
+        ("eq", 2) => Op::Fn2(eq),
+        ("ne", 2) => Op::Fn2(ne),
+        ("and", 2) => Op::Fn2(and),
+        ("or", 2) => Op::Fn2(or),
+        ("range", 3) => Op::Fn3(range),
+        ("clamp", 3) => Op::Fn3(clamp),
+        ("select", 3) => Op::Fn3(select),
+        ("mix", 3) => Op::Fn3(mix),
+        _ => return None,
+    };
+    Some(op)
+}
@@ -4,21 +4,28 @@
 //!
 //! Sources to connect: input to delay, delay time, gain.
 use delay::Delay;
+use interpolation::Interpolation;
 use sample::Sample;
 
 pub struct Feedback {
     delay: Delay,
     output: Sample,
+    sample_period: Sample,
 }
 
 impl Feedback {
-    pub fn new(sample_rate: usize, max_delay: Sample) -> Self {
-        let delay = Delay::new(sample_rate, max_delay);
-        Feedback { delay, output: 0.0 }
+    pub fn new(sample_rate: usize, max_delay: Sample, interpolation: Interpolation) -> Self {
+        let delay = Delay::new(sample_rate, max_delay, interpolation);
+        Feedback {
+            delay,
+            output: 0.0,
+            sample_period: 1.0 / sample_rate as Sample,
+        }
     }
 
     pub fn sample(&mut self, input: Sample, delay: Sample, gain: Sample) -> Sample {
-        let delayed = self.delay.sample(self.output, delay);
+        // Previous output is already one frame old.
+        let delayed = self.delay.sample(self.output, delay - self.sample_period);
         self.output = input + gain * delayed;
         self.output
     }
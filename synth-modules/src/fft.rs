@@ -0,0 +1,138 @@
+//! # FFT
+//!
+//! Complex numbers and in-place radix-2 fast Fourier transform.
+//!
+//! Twiddle factors and bit reversal permutation are precomputed, so transform doesn't allocate
+//! memory and could be performed in the audio thread.
+use sample::Sample;
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex {
+    pub re: Sample,
+    pub im: Sample,
+}
+
+impl Complex {
+    pub fn new(re: Sample, im: Sample) -> Self {
+        Complex { re, im }
+    }
+
+    pub fn from_polar(norm: Sample, arg: Sample) -> Self {
+        Complex::new(norm * arg.cos(), norm * arg.sin())
+    }
+
+    pub fn norm(self) -> Sample {
+        self.re.hypot(self.im)
+    }
+
+    pub fn arg(self) -> Sample {
+        self.im.atan2(self.re)
+    }
+
+    pub fn scale(self, k: Sample) -> Self {
+        Complex::new(k * self.re, k * self.im)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+pub struct FFT {
+    /// exp(-2πik/N) for k in 0..N/2
+    twiddles: Vec<Complex>,
+    /// Bit reversal permutation of indices.
+    reversed: Vec<usize>,
+}
+
+impl FFT {
+    /// Prepare transform of the given size, which must be a power of two.
+    pub fn new(size: usize) -> Self {
+        assert!(size.is_power_of_two(), "FFT size must be a power of two");
+        let twiddles = (0..size / 2)
+            .map(|k| Complex::from_polar(1.0, -2.0 * PI * k as Sample / size as Sample))
+            .collect();
+        let bits = size.trailing_zeros();
+        let reversed = (0..size)
+            .map(|i| {
+                if bits == 0 {
+                    i
+                } else {
+                    i.reverse_bits() >> (usize::BITS - bits)
+                }
+            }).collect();
+        FFT { twiddles, reversed }
+    }
+
+    pub fn size(&self) -> usize {
+        self.reversed.len()
+    }
+
+    /// Transform signal into spectrum in place.
+    pub fn forward(&self, buffer: &mut [Complex]) {
+        self.transform(buffer, false);
+    }
+
+    /// Transform spectrum back into signal in place, result is scaled by 1/N.
+    pub fn inverse(&self, buffer: &mut [Complex]) {
+        self.transform(buffer, true);
+        let k = (self.size() as Sample).recip();
+        for x in buffer.iter_mut() {
+            *x = x.scale(k);
+        }
+    }
+
+    fn transform(&self, buffer: &mut [Complex], is_inverse: bool) {
+        let size = self.size();
+        assert_eq!(buffer.len(), size, "Buffer length must match FFT size");
+        for i in 0..size {
+            let j = self.reversed[i];
+            if i < j {
+                buffer.swap(i, j);
+            }
+        }
+        let mut length = 2;
+        while length <= size {
+            let half = length / 2;
+            let stride = size / length;
+            for start in (0..size).step_by(length) {
+                for k in 0..half {
+                    let mut twiddle = self.twiddles[k * stride];
+                    if is_inverse {
+                        twiddle.im = -twiddle.im;
+                    }
+                    let a = buffer[start + k];
+                    let b = buffer[start + k + half] * twiddle;
+                    buffer[start + k] = a + b;
+                    buffer[start + k + half] = a - b;
+                }
+            }
+            length *= 2;
+        }
+    }
+}
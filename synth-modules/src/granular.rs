@@ -0,0 +1,159 @@
+//! # Granular
+//!
+//! Granular synthesis engine. Grains are short Hann-windowed fragments of the buffer played back
+//! simultaneously. Buffer is either a sample loaded into memory, or a ring buffer continuously
+//! recording the input signal, just like the one in `Delay`.
+//!
+//! New grain is spawned `density` times per second. It lasts `size` seconds and reads the buffer at
+//! `pitch` rate (1 is original pitch, 2 is an octave up, negative plays backwards) starting from
+//! `position`. Position is relative to the buffer length: for a loaded sample 0 is its beginning
+//! and 1 is its end, for a live buffer 0 is the most recent input and 1 is the oldest one.
+//! `spray` randomly displaces grain starting position by up to the given fraction of the buffer
+//! length in both directions.
+//!
+//! Sources to connect: input (ignored when playing loaded sample), size, density, position, pitch,
+//! spray.
+use sample::Sample;
+use std::f64::consts::PI;
+
+/// Maximum number of simultaneously playing grains.
+/// Grains are preallocated to avoid memory allocation in the audio thread,
+/// new grains are dropped when all of them are busy.
+const MAX_GRAINS: usize = 128;
+
+#[derive(Clone, Copy, Default)]
+struct Grain {
+    /// Buffer position in frames, could be out of the buffer bounds and is wrapped on reading.
+    position: Sample,
+    /// How many frames to advance position per output frame.
+    rate: Sample,
+    /// How many frames grain has already played.
+    age: usize,
+    /// How many frames grain plays in total.
+    length: usize,
+}
+
+pub struct Granular {
+    buffer: Vec<Sample>,
+    /// Write position of the live buffer, `None` when grains are read from a loaded sample.
+    frame_number: Option<usize>,
+    /// Grains pool, grain is active when its age is less than length.
+    grains: Vec<Grain>,
+    /// Accumulates `density` and spawns a new grain every time it reaches 1.
+    clock: Sample,
+    /// Ratio of the buffer's sample rate to the output one.
+    rate_ratio: Sample,
+    sample_rate: Sample,
+    /// State of the xorshift generator used for spray.
+    seed: u64,
+}
+
+impl Granular {
+    /// Create granular engine reading grains from the input recorded up to `max_length` seconds ago.
+    pub fn new(sample_rate: usize, max_length: Sample) -> Self {
+        // next_power_of_two to trade memory for speed by replacing `mod` with `&`
+        let length = ((sample_rate as Sample * max_length) as usize + 1).next_power_of_two();
+        let mut granular = Granular::from_buffer(sample_rate, vec![0.0; length], sample_rate);
+        granular.frame_number = Some(0);
+        granular
+    }
+
+    /// Create granular engine reading grains from the given sample.
+    pub fn from_buffer(sample_rate: usize, buffer: Vec<Sample>, buffer_sample_rate: usize) -> Self {
+        Granular {
+            buffer,
+            frame_number: None,
+            grains: vec![Grain::default(); MAX_GRAINS],
+            clock: 1.0,
+            rate_ratio: buffer_sample_rate as Sample / sample_rate as Sample,
+            sample_rate: sample_rate as Sample,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    pub fn sample(
+        &mut self,
+        input: Sample,
+        size: Sample,
+        density: Sample,
+        position: Sample,
+        pitch: Sample,
+        spray: Sample,
+    ) -> Sample {
+        if self.buffer.is_empty() {
+            return 0.0;
+        }
+        if let Some(frame_number) = self.frame_number {
+            let mask = self.buffer.len() - 1;
+            self.buffer[frame_number & mask] = input;
+            self.frame_number = Some(frame_number + 1);
+        }
+        if self.clock >= 1.0 {
+            self.clock -= self.clock.floor();
+            self.spawn(size, position, pitch, spray);
+        }
+        self.clock += density.max(0.0) / self.sample_rate;
+
+        let mut output = 0.0;
+        for i in 0..self.grains.len() {
+            let grain = self.grains[i];
+            if grain.age >= grain.length {
+                continue;
+            }
+            let window = 0.5 - 0.5 * (2.0 * PI * grain.age as Sample / grain.length as Sample).cos();
+            output += window * self.read(grain.position);
+            let grain = &mut self.grains[i];
+            grain.position += grain.rate;
+            grain.age += 1;
+        }
+        output
+    }
+
+    /// Start a new grain if there is a free one in the pool.
+    fn spawn(&mut self, size: Sample, position: Sample, pitch: Sample, spray: Sample) {
+        let length = (size * self.sample_rate) as usize;
+        if length == 0 {
+            return;
+        }
+        let spray = spray * (2.0 * self.random() - 1.0);
+        let position = (position + spray).clamp(0.0, 1.0);
+        let buffer_length = self.buffer.len() as Sample;
+        let rate = pitch * self.rate_ratio;
+        let position = match self.frame_number {
+            Some(frame_number) => {
+                // Grain must not overtake the write head, otherwise it would read stale data.
+                let min_delay = ((rate - 1.0) * length as Sample).max(0.0) + 1.0;
+                let delay = (position * buffer_length).max(min_delay);
+                frame_number as Sample - delay
+            }
+            None => position * (buffer_length - 1.0),
+        };
+        if let Some(grain) = self.grains.iter_mut().find(|g| g.age >= g.length) {
+            *grain = Grain {
+                position,
+                rate,
+                age: 0,
+                length,
+            };
+        }
+    }
+
+    /// Read buffer at fractional position with linear interpolation, wrapping it around the buffer.
+    fn read(&self, position: Sample) -> Sample {
+        let length = self.buffer.len();
+        let position = position.rem_euclid(length as Sample);
+        let i = position as usize % length;
+        let k = position.fract();
+        let a = self.buffer[i];
+        let b = self.buffer[(i + 1) % length];
+        (1.0 - k) * a + k * b
+    }
+
+    /// Generate uniformly distributed random number in the range 0..1 with xorshift64.
+    fn random(&mut self) -> Sample {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 11) as Sample / (1_u64 << 53) as Sample
+    }
+}
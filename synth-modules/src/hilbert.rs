@@ -0,0 +1,109 @@
+//! # Hilbert transform
+//!
+//! Pair of all-pass filter chains which outputs have (approximately) 90° phase difference over the
+//! audio range, i.e. they form a quadrature (analytic) signal. Coefficients are the ones designed by
+//! Olli Niemitalo, phase difference error is below 0.7° in the range 20 Hz..20 kHz at 44.1 kHz.
+//!
+//! Sources to connect: input.
+use sample::Sample;
+use std::f64::consts::PI;
+
+/// Second order all-pass section y[n] = a^2 * (x[n] + y[n-2]) - x[n-2].
+#[derive(Clone, Copy)]
+struct AllPass {
+    coefficient: Sample,
+    x: [Sample; 2],
+    y: [Sample; 2],
+}
+
+impl AllPass {
+    fn new(a: Sample) -> Self {
+        AllPass {
+            coefficient: a * a,
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    fn sample(&mut self, x: Sample) -> Sample {
+        let y = self.coefficient * (x + self.y[1]) - self.x[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+pub struct Hilbert {
+    real: [AllPass; 4],
+    imaginary: [AllPass; 4],
+    /// Real path has an extra one sample delay.
+    delayed: Sample,
+}
+
+impl Hilbert {
+    pub fn new() -> Self {
+        Hilbert {
+            real: [
+                AllPass::new(0.692_387_9),
+                AllPass::new(0.936_065_432_295_9),
+                AllPass::new(0.988_229_522_686),
+                AllPass::new(0.998_748_845_273_7),
+            ],
+            imaginary: [
+                AllPass::new(0.402_192_116_242_6),
+                AllPass::new(0.856_171_088_242),
+                AllPass::new(0.972_290_954_565_1),
+                AllPass::new(0.995_288_479_127_8),
+            ],
+            delayed: 0.0,
+        }
+    }
+
+    /// Return real and imaginary parts of the analytic signal.
+    pub fn sample(&mut self, input: Sample) -> (Sample, Sample) {
+        let real = self.real.iter_mut().fold(input, |x, f| f.sample(x));
+        let imaginary = self.imaginary.iter_mut().fold(input, |x, f| f.sample(x));
+        let delayed = self.delayed;
+        self.delayed = real;
+        // Imaginary path leads the real one, negate it to get lagging Hilbert transform.
+        (delayed, -imaginary)
+    }
+}
+
+impl Default for Hilbert {
+    fn default() -> Self {
+        Hilbert::new()
+    }
+}
+
+/// # Frequency shifter
+///
+/// Single-sideband modulation: shift all frequencies of the signal by the same amount (Hz),
+/// up for positive shift and down for negative one. Unlike pitch shifting, it doesn't preserve
+/// harmonic relations, producing metallic and detuned sounds, or barber-pole phasing when shift is
+/// just a few Hz.
+///
+/// Sources to connect: input, shift.
+pub struct FrequencyShifter {
+    hilbert: Hilbert,
+    /// Phase of the quadrature oscillator in cycles, wrapped to 0..1.
+    phase: Sample,
+    sample_period: Sample,
+}
+
+impl FrequencyShifter {
+    pub fn new(sample_rate: usize) -> Self {
+        FrequencyShifter {
+            hilbert: Hilbert::new(),
+            phase: 0.0,
+            sample_period: (sample_rate as Sample).recip(),
+        }
+    }
+
+    pub fn sample(&mut self, input: Sample, shift: Sample) -> Sample {
+        let (real, imaginary) = self.hilbert.sample(input);
+        let angle = 2.0 * PI * self.phase;
+        self.phase = (self.phase + shift * self.sample_period).rem_euclid(1.0);
+        real * angle.cos() - imaginary * angle.sin()
+    }
+}
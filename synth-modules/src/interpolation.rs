@@ -0,0 +1,62 @@
+//! # Interpolation
+//!
+//! Helpers to read signal between samples.
+use sample::Sample;
+use std::str::FromStr;
+
+/// Method to read signal between samples, from the cheapest to the most elaborate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    /// Round down to the nearest sample.
+    None,
+    Linear,
+    /// See `hermite`.
+    Hermite,
+    /// See `lagrange`.
+    Lagrange,
+    /// First-order all-pass filter. Has flat magnitude response and so doesn't dull highs, but
+    /// keeps state and thus is suitable only for a single read head with slowly varying position.
+    AllPass,
+}
+
+/// Parse method from its lowercase name: none, linear, hermite, lagrange or allpass.
+impl FromStr for Interpolation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Interpolation::None),
+            "linear" => Ok(Interpolation::Linear),
+            "hermite" => Ok(Interpolation::Hermite),
+            "lagrange" => Ok(Interpolation::Lagrange),
+            "allpass" => Ok(Interpolation::AllPass),
+            _ => Err(format!("Unknown interpolation: {}", s)),
+        }
+    }
+}
+
+/// Linear interpolation between `a` and `b` at fractional position `k` (0 is `a`, 1 is `b`).
+pub fn linear(a: Sample, b: Sample, k: Sample) -> Sample {
+    a + k * (b - a)
+}
+
+/// 4-point 3rd-order Hermite interpolation between `x1` and `x2` at fractional position `k`
+/// (0 is `x1`, 1 is `x2`). `x0` and `x3` are neighbours used to estimate slopes.
+/// Continuous first derivative makes it a lot less noisy than linear interpolation when signal is
+/// resampled at varying rate.
+pub fn hermite(x0: Sample, x1: Sample, x2: Sample, x3: Sample, k: Sample) -> Sample {
+    let c1 = 0.5 * (x2 - x0);
+    let c2 = x0 - 2.5 * x1 + 2.0 * x2 - 0.5 * x3;
+    let c3 = 0.5 * (x3 - x0) + 1.5 * (x1 - x2);
+    ((c3 * k + c2) * k + c1) * k + x1
+}
+
+/// 4-point 3rd-order Lagrange interpolation between `x1` and `x2` at fractional position `k`
+/// (0 is `x1`, 1 is `x2`). Polynomial passes through all four points `x0`..`x3`, which makes it
+/// flatter in the passband than Hermite at the cost of discontinuous derivative.
+pub fn lagrange(x0: Sample, x1: Sample, x2: Sample, x3: Sample, k: Sample) -> Sample {
+    let a = k + 1.0;
+    let b = k - 1.0;
+    let c = k - 2.0;
+    -x0 * k * b * c / 6.0 + x1 * a * b * c / 2.0 - x2 * a * k * c / 2.0 + x3 * a * k * b / 6.0
+}
@@ -1,11 +1,32 @@
 //! # Synth modules
 //!
 //! Collection of backend-agnostic DSP modules. Provides basic building blocks for modular synthesis environment.
+pub mod biquad;
 pub mod constant;
 pub mod delay;
+pub mod envelope;
+pub mod expr;
 pub mod feedback;
+pub mod fft;
+pub mod granular;
+pub mod hilbert;
+pub mod interpolation;
+pub mod looper;
+pub mod modal;
+pub mod multitap;
+pub mod operator;
 pub mod phasor;
+pub mod ping_pong;
+pub mod pitch;
+pub mod pitch_shifter;
 pub mod prelude;
 pub mod pure;
 pub mod rc_filter;
 pub mod sample;
+pub mod sampler;
+pub mod spectral;
+pub mod stft;
+pub mod trigger;
+pub mod varispeed;
+pub mod vocoder;
+pub mod waveguide;
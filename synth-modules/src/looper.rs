@@ -0,0 +1,136 @@
+//! # Looper
+//!
+//! Live looper with explicit transport state.
+//!
+//! `record` trigger cycles through the states:
+//!
+//! ```text
+//! empty -> recording -> playing -> overdubbing -> playing -> overdubbing -> ...
+//! ```
+//!
+//! The first recording sets the loop length, which is limited by the buffer allocated on start.
+//! While overdubbing, input is mixed into the loop and existing material is multiplied by
+//! `feedback`, so older layers could fade out gradually. `clear` trigger erases the loop.
+//!
+//! When looper is synchronised, triggers are not applied immediately but wait for the next `clock`
+//! trigger, so loop boundaries are aligned to the clock.
+//!
+//! Sources to connect: input, record, clear, feedback, clock (ignored when not synchronised).
+use sample::Sample;
+use trigger::Trigger;
+
+#[derive(Clone, Copy)]
+enum State {
+    Empty,
+    Recording,
+    Playing,
+    Overdubbing,
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    Record,
+    Clear,
+}
+
+pub struct Looper {
+    buffer: Vec<Sample>,
+    /// Length of the recorded loop in frames.
+    length: usize,
+    /// Playback position in frames.
+    position: usize,
+    state: State,
+    is_synced: bool,
+    /// Action triggered but not applied yet, because synchronised looper waits for clock.
+    pending_action: Option<Action>,
+    record_trigger: Trigger,
+    clear_trigger: Trigger,
+    clock_trigger: Trigger,
+}
+
+impl Looper {
+    pub fn new(sample_rate: usize, max_length: Sample, is_synced: bool) -> Self {
+        let buffer = vec![0.0; (sample_rate as Sample * max_length) as usize];
+        Looper {
+            buffer,
+            length: 0,
+            position: 0,
+            state: State::Empty,
+            is_synced,
+            pending_action: None,
+            record_trigger: Trigger::new(),
+            clear_trigger: Trigger::new(),
+            clock_trigger: Trigger::new(),
+        }
+    }
+
+    pub fn sample(
+        &mut self,
+        input: Sample,
+        record: Sample,
+        clear: Sample,
+        feedback: Sample,
+        clock: Sample,
+    ) -> Sample {
+        let is_clock_triggered = self.clock_trigger.is_triggered(clock);
+        if self.record_trigger.is_triggered(record) {
+            self.pending_action = Some(Action::Record);
+        }
+        // Clear takes precedence over record when both are triggered at once.
+        if self.clear_trigger.is_triggered(clear) {
+            self.pending_action = Some(Action::Clear);
+        }
+        if !self.is_synced || is_clock_triggered {
+            if let Some(action) = self.pending_action.take() {
+                self.apply(action);
+            }
+        }
+        match self.state {
+            State::Empty => 0.0,
+            State::Recording => {
+                if self.length < self.buffer.len() {
+                    self.buffer[self.length] = input;
+                    self.length += 1;
+                } else {
+                    // Buffer is full, close the loop.
+                    self.apply(Action::Record);
+                }
+                0.0
+            }
+            State::Playing => {
+                let output = self.buffer[self.position];
+                self.position = (self.position + 1) % self.length;
+                output
+            }
+            State::Overdubbing => {
+                let output = self.buffer[self.position];
+                self.buffer[self.position] = feedback * output + input;
+                self.position = (self.position + 1) % self.length;
+                output
+            }
+        }
+    }
+
+    fn apply(&mut self, action: Action) {
+        self.state = match (action, self.state) {
+            (Action::Clear, _) => {
+                self.length = 0;
+                State::Empty
+            }
+            (Action::Record, State::Empty) => {
+                self.length = 0;
+                State::Recording
+            }
+            (Action::Record, State::Recording) => {
+                self.position = 0;
+                if self.length > 0 {
+                    State::Playing
+                } else {
+                    State::Empty
+                }
+            }
+            (Action::Record, State::Playing) => State::Overdubbing,
+            (Action::Record, State::Overdubbing) => State::Playing,
+        };
+    }
+}
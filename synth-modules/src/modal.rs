@@ -0,0 +1,81 @@
+//! # Modal resonator
+//!
+//! Bank of decaying sine resonators excited by the input signal. Every resonance (mode) has its
+//! frequency relative to the fundamental, decay time and amplitude. Bells, bars, plates and
+//! membranes are well approximated by a dozen of carefully tuned modes.
+//!
+//! Sources to connect: excitation input, fundamental frequency.
+use sample::Sample;
+use std::f64::consts::PI;
+
+/// Single mode of the resonator.
+#[derive(Clone, Copy, Debug)]
+pub struct Resonance {
+    /// Frequency relative to the fundamental.
+    pub ratio: Sample,
+    /// Time to decay by 60 dB (seconds).
+    pub decay: Sample,
+    /// Amplitude of the response to the unit impulse.
+    pub amplitude: Sample,
+}
+
+struct Resonator {
+    resonance: Resonance,
+    /// Last valid angular frequency of the mode (radians per frame).
+    w: Sample,
+    y1: Sample,
+    y2: Sample,
+}
+
+pub struct Modal {
+    resonators: Vec<Resonator>,
+    sample_rate: Sample,
+}
+
+impl Modal {
+    pub fn new(sample_rate: usize, resonances: &[Resonance]) -> Self {
+        let resonators = resonances
+            .iter()
+            .map(|&resonance| Resonator {
+                resonance,
+                w: 0.0,
+                y1: 0.0,
+                y2: 0.0,
+            }).collect();
+        Modal {
+            resonators,
+            sample_rate: sample_rate as Sample,
+        }
+    }
+
+    pub fn sample(&mut self, x: Sample, frequency: Sample) -> Sample {
+        let sample_rate = self.sample_rate;
+        let mut output = 0.0;
+        for resonator in &mut self.resonators {
+            let Resonance {
+                ratio,
+                decay,
+                amplitude,
+            } = resonator.resonance;
+            let w = 2.0 * PI * ratio * frequency / sample_rate;
+            // Modes pushed out of (0, Nyquist) would alias or collapse into DC, so they are not
+            // excited anymore and ring out at the last valid frequency.
+            let x = if w > 0.0 && w < PI {
+                resonator.w = w;
+                x
+            } else {
+                0.0
+            };
+            let w = resonator.w;
+            // -60 dB in `decay` seconds.
+            let r = (-6.907_755_278_982_137 / (decay * sample_rate).max(1.0)).exp();
+            // Impulse response is `amplitude * r^n * sin(w * (n + 1))`.
+            let y =
+                amplitude * w.sin() * x + 2.0 * r * w.cos() * resonator.y1 - r * r * resonator.y2;
+            resonator.y2 = resonator.y1;
+            resonator.y1 = y;
+            output += y;
+        }
+        output
+    }
+}
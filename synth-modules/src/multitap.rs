@@ -0,0 +1,35 @@
+//! # Multi-tap delay
+//!
+//! Delay line with several read heads, each one with its own delay time and gain.
+//! Output is the sum of all taps.
+//!
+//! Sources to connect: input to delay, delay time and gain for every tap.
+use delay::Delay;
+use interpolation::Interpolation;
+use sample::Sample;
+
+pub struct MultiTap {
+    delay: Delay,
+    taps: usize,
+}
+
+impl MultiTap {
+    pub fn new(sample_rate: usize, max_delay: Sample, taps: usize) -> Self {
+        MultiTap {
+            delay: Delay::new(sample_rate, max_delay, Interpolation::Hermite),
+            taps,
+        }
+    }
+
+    /// `times` and `gains` are expected to have `taps` elements, extra ones are ignored.
+    pub fn sample(&mut self, x: Sample, times: &[Sample], gains: &[Sample]) -> Sample {
+        self.delay.push(x);
+        let delay = &mut self.delay;
+        times
+            .iter()
+            .zip(gains)
+            .take(self.taps)
+            .map(|(&time, &gain)| gain * delay.tap(time))
+            .sum()
+    }
+}
@@ -0,0 +1,152 @@
+//! # Operator
+//!
+//! Sine oscillator with phase modulation input and self-feedback, the building block of FM
+//! synthesis. Just like in the classic FM synths, frequency modulation is implemented as phase
+//! modulation, which keeps pitch stable regardless of the modulation amount.
+//!
+//! Phase modulation and feedback are expressed in radians, so sine modulator multiplied by
+//! modulation index could be connected directly.
+//!
+//! Sources to connect: frequency, phase modulation, feedback.
+use sample::Sample;
+use std::f64::consts::PI;
+
+pub struct Operator {
+    /// Phase in cycles, wrapped to 0..1.
+    phase: Sample,
+    sample_period: Sample,
+    /// Two last outputs. Feedback uses their average to tame the "hunting" of the feedback loop.
+    history: [Sample; 2],
+}
+
+impl Operator {
+    pub fn new(sample_rate: usize) -> Self {
+        Operator {
+            phase: 0.0,
+            sample_period: (sample_rate as Sample).recip(),
+            history: [0.0; 2],
+        }
+    }
+
+    pub fn sample(&mut self, frequency: Sample, modulation: Sample, feedback: Sample) -> Sample {
+        let feedback = feedback * 0.5 * (self.history[0] + self.history[1]);
+        let output = (2.0 * PI * self.phase + modulation + feedback).sin();
+        self.phase = (self.phase + frequency * self.sample_period).rem_euclid(1.0);
+        self.history = [output, self.history[0]];
+        output
+    }
+}
+
+/// How many operators FM voice has.
+pub const OPERATORS: usize = 4;
+
+/// FM algorithm defines how operators are connected.
+/// Operators are numbered from 1 to 4, only operators with higher number could modulate ones with
+/// lower number, and operator 4 is the one with feedback.
+struct Algorithm {
+    /// modulators[i] lists operators which modulate operator i + 1.
+    modulators: [&'static [usize]; OPERATORS],
+    /// Operators which outputs are mixed to the voice output.
+    carriers: &'static [usize],
+}
+
+/// Number of available FM algorithms.
+pub const ALGORITHMS_COUNT: usize = 8;
+
+/// Algorithms supported by FM voice, selected by number starting from 1:
+///
+/// ```text
+/// 1: 4 -> 3 -> 2 -> 1
+/// 2: (3 + 4) -> 2 -> 1
+/// 3: (3 -> 2) + 4 -> 1
+/// 4: (4 -> 3) + 2 -> 1
+/// 5: (2 -> 1) + (4 -> 3)
+/// 6: 4 -> (1 + 2 + 3)
+/// 7: 1 + 2 + (4 -> 3)
+/// 8: 1 + 2 + 3 + 4
+/// ```
+const ALGORITHMS: [Algorithm; ALGORITHMS_COUNT] = [
+    Algorithm {
+        modulators: [&[2], &[3], &[4], &[]],
+        carriers: &[1],
+    },
+    Algorithm {
+        modulators: [&[2], &[3, 4], &[], &[]],
+        carriers: &[1],
+    },
+    Algorithm {
+        modulators: [&[2, 4], &[3], &[], &[]],
+        carriers: &[1],
+    },
+    Algorithm {
+        modulators: [&[2, 3], &[], &[4], &[]],
+        carriers: &[1],
+    },
+    Algorithm {
+        modulators: [&[2], &[], &[4], &[]],
+        carriers: &[1, 3],
+    },
+    Algorithm {
+        modulators: [&[4], &[4], &[4], &[]],
+        carriers: &[1, 2, 3],
+    },
+    Algorithm {
+        modulators: [&[], &[], &[4], &[]],
+        carriers: &[1, 2, 3],
+    },
+    Algorithm {
+        modulators: [&[], &[], &[], &[]],
+        carriers: &[1, 2, 3, 4],
+    },
+];
+
+/// FM voice of four operators connected according to the selected algorithm.
+/// Operators frequencies are multiples of the voice frequency set by ratios.
+/// Modulation index scales all modulators outputs, feedback is applied to operator 4.
+///
+/// Sources to connect: frequency, modulation index, feedback.
+pub struct FMVoice {
+    operators: [Operator; OPERATORS],
+    ratios: [Sample; OPERATORS],
+    algorithm: &'static Algorithm,
+}
+
+impl FMVoice {
+    /// Create FM voice with the given algorithm number (1..=8) and operators frequency ratios.
+    /// Panics if algorithm number is out of range.
+    pub fn new(sample_rate: usize, algorithm: usize, ratios: [Sample; OPERATORS]) -> Self {
+        assert!(
+            (1..=ALGORITHMS_COUNT).contains(&algorithm),
+            "FM algorithm must be in the range 1..={}",
+            ALGORITHMS_COUNT
+        );
+        FMVoice {
+            operators: [
+                Operator::new(sample_rate),
+                Operator::new(sample_rate),
+                Operator::new(sample_rate),
+                Operator::new(sample_rate),
+            ],
+            ratios,
+            algorithm: &ALGORITHMS[algorithm - 1],
+        }
+    }
+
+    pub fn sample(&mut self, frequency: Sample, index: Sample, feedback: Sample) -> Sample {
+        let mut outputs = [0.0; OPERATORS];
+        // Modulators always have higher numbers, so evaluating from the top guarantees that their
+        // outputs are ready.
+        for i in (0..OPERATORS).rev() {
+            let modulation = index
+                * self.algorithm.modulators[i]
+                    .iter()
+                    .map(|&m| outputs[m - 1])
+                    .sum::<Sample>();
+            let feedback = if i == OPERATORS - 1 { feedback } else { 0.0 };
+            outputs[i] =
+                self.operators[i].sample(frequency * self.ratios[i], modulation, feedback);
+        }
+        let carriers = self.algorithm.carriers;
+        carriers.iter().map(|&c| outputs[c - 1]).sum::<Sample>() / carriers.len() as Sample
+    }
+}
@@ -1,6 +1,6 @@
 //! # Phasor
 //!
-//! ```
+//! ```text
 //!  1     /|    /|    /|    /|
 //!       / |   / |   / |   / |
 //!  0   /  |  /  |  /  |  /  |
@@ -14,26 +14,45 @@
 //! just pure transformations then and are not required to care about handling varying frequency by
 //! themselves anymore.
 //!
-//! Sources to connect: frequency.
+//! When reset signal crosses zero going up, phase jumps back to the initial one. Connecting another
+//! phasor to reset provides classic oscillator hard sync.
+//!
+//! Sources to connect: frequency, reset.
 
 use sample::Sample;
+use trigger::Trigger;
 
 pub struct Phasor {
     phase: Sample,
+    initial_phase: Sample,
     sample_period: Sample,
+    reset: Trigger,
 }
 
 impl Phasor {
-    pub fn new(sample_rate: usize) -> Self {
+    /// Create phasor which starts from (and is reset to) `initial_phase` in the range -1..1.
+    pub fn new(sample_rate: usize, initial_phase: Sample) -> Self {
+        let initial_phase = wrap(initial_phase);
         Phasor {
-            phase: 0.0,
+            phase: initial_phase,
+            initial_phase,
             sample_period: (sample_rate as Sample).recip(),
+            reset: Trigger::new(),
         }
     }
 
-    pub fn sample(&mut self, frequency: Sample) -> Sample {
-        let dx = frequency * self.sample_period;
-        self.phase = (self.phase + dx + 1.0) % 2.0 - 1.0;
+    pub fn sample(&mut self, frequency: Sample, reset: Sample) -> Sample {
+        if self.reset.is_triggered(reset) {
+            self.phase = self.initial_phase;
+        } else {
+            let dx = frequency * self.sample_period;
+            self.phase = wrap(self.phase + dx);
+        }
         self.phase
     }
 }
+
+/// Wrap phase into the range -1..1, negative frequencies and phases included.
+fn wrap(phase: Sample) -> Sample {
+    (phase + 1.0).rem_euclid(2.0) - 1.0
+}
@@ -0,0 +1,52 @@
+//! # Ping-pong delay
+//!
+//! Stereo delay with cross-feedback: echoes of the left channel are fed back into the right one
+//! and vice versa, so they bounce between channels. Feedback path is damped by low-pass filters,
+//! like in tape echo every repetition is darker than the previous one.
+//!
+//! Sources to connect: left input, right input, delay time, feedback gain, damping (cut-off
+//! frequency of the feedback path filter).
+use delay::Delay;
+use interpolation::Interpolation;
+use rc_filter::LPF;
+use sample::Sample;
+
+pub struct PingPong {
+    left: Delay,
+    right: Delay,
+    left_damping: LPF,
+    right_damping: LPF,
+    sample_period: Sample,
+}
+
+impl PingPong {
+    pub fn new(sample_rate: usize, max_delay: Sample) -> Self {
+        PingPong {
+            left: Delay::new(sample_rate, max_delay, Interpolation::Hermite),
+            right: Delay::new(sample_rate, max_delay, Interpolation::Hermite),
+            left_damping: LPF::new(sample_rate),
+            right_damping: LPF::new(sample_rate),
+            sample_period: 1.0 / sample_rate as Sample,
+        }
+    }
+
+    /// Return wet left and right signals.
+    pub fn sample(
+        &mut self,
+        left: Sample,
+        right: Sample,
+        delay: Sample,
+        feedback: Sample,
+        damping: Sample,
+    ) -> (Sample, Sample) {
+        // Lines are read before the current input is pushed, hence one frame less.
+        let delay = delay - self.sample_period;
+        let left_output = self.left.tap(delay);
+        let right_output = self.right.tap(delay);
+        let left_feedback = self.left_damping.sample(left_output, damping);
+        let right_feedback = self.right_damping.sample(right_output, damping);
+        self.left.push(left + feedback * right_feedback);
+        self.right.push(right + feedback * left_feedback);
+        (left_output, right_output)
+    }
+}
@@ -0,0 +1,159 @@
+//! # Pitch tracker
+//!
+//! Estimate fundamental frequency of the signal with the YIN algorithm.
+//!
+//! Input is collected into the window holding two periods of the lowest detectable frequency.
+//! Window is analysed every hop, which is a quarter of the window, and the estimation is held in
+//! between. Analysis is spread over the frames of the hop to keep the cost of every sample call
+//! bounded, so the estimation lags by one more hop. Along with the frequency tracker reports
+//! confidence in the range 0..1, which is close to 1 for clean periodic signals and drops for noise
+//! and silence. When there is no periodicity at all, confidence is 0 and the last detected
+//! frequency is held.
+//!
+//! Sources to connect: input.
+use sample::Sample;
+
+/// Threshold of the cumulative mean normalized difference below which the first dip is accepted as
+/// the period. YIN paper suggests values in the range 0.1..0.15.
+const THRESHOLD: Sample = 0.15;
+
+pub struct PitchTracker {
+    /// Ring buffer of the window size.
+    buffer: Vec<Sample>,
+    frame_number: usize,
+    /// Window copied from the ring buffer in chronological order, preallocated for analysis.
+    window: Vec<Sample>,
+    /// Cumulative mean normalized difference function, preallocated for analysis.
+    difference: Vec<Sample>,
+    /// Next period to compute the difference for, analysis is done when it exceeds `max_period`.
+    period: usize,
+    /// Running sum of the difference function for the cumulative mean.
+    sum: Sample,
+    /// How many periods to compute every frame to finish analysis within the hop.
+    periods_per_frame: usize,
+    /// Shortest period (frames) to look for.
+    min_period: usize,
+    /// Longest period (frames) to look for, also the length of the integration window.
+    max_period: usize,
+    hop: usize,
+    /// Frames since the last hop.
+    hop_frame: usize,
+    sample_rate: Sample,
+    frequency: Sample,
+    confidence: Sample,
+}
+
+impl PitchTracker {
+    pub fn new(sample_rate: usize, min_frequency: Sample, max_frequency: Sample) -> Self {
+        let sample_rate = sample_rate as Sample;
+        let max_period = ((sample_rate / min_frequency).ceil() as usize).max(4);
+        let min_period = ((sample_rate / max_frequency) as usize).max(2).min(max_period - 1);
+        let window_size = 2 * max_period;
+        let hop = window_size / 4;
+        PitchTracker {
+            buffer: vec![0.0; window_size],
+            frame_number: 0,
+            window: vec![0.0; window_size],
+            difference: vec![0.0; max_period + 1],
+            period: max_period + 1,
+            sum: 0.0,
+            // Rounded up, finishing a bit earlier is fine.
+            periods_per_frame: max_period / hop + 1,
+            min_period,
+            max_period,
+            hop,
+            hop_frame: 0,
+            sample_rate,
+            frequency: 0.0,
+            confidence: 0.0,
+        }
+    }
+
+    /// Return estimated frequency and confidence.
+    pub fn sample(&mut self, input: Sample) -> (Sample, Sample) {
+        let window_size = self.buffer.len();
+        self.buffer[self.frame_number % window_size] = input;
+        self.frame_number += 1;
+        self.hop_frame += 1;
+        if self.hop_frame == self.hop {
+            self.hop_frame = 0;
+            self.start_analysis();
+        }
+        if self.period <= self.max_period {
+            self.continue_analysis();
+        }
+        (self.frequency, self.confidence)
+    }
+
+    fn start_analysis(&mut self) {
+        let window_size = self.buffer.len();
+        for (i, x) in self.window.iter_mut().enumerate() {
+            *x = self.buffer[(self.frame_number + i) % window_size];
+        }
+        self.difference[0] = 1.0;
+        self.period = 1;
+        self.sum = 0.0;
+    }
+
+    /// Compute the next chunk of the difference function normalized by its cumulative mean,
+    /// and estimate frequency when it's complete.
+    fn continue_analysis(&mut self) {
+        let last = (self.period + self.periods_per_frame - 1).min(self.max_period);
+        for period in self.period..=last {
+            let d = self.window[..self.max_period]
+                .iter()
+                .zip(&self.window[period..period + self.max_period])
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<Sample>();
+            self.sum += d;
+            self.difference[period] = if self.sum > 0.0 {
+                d * period as Sample / self.sum
+            } else {
+                1.0
+            };
+        }
+        self.period = last + 1;
+        if self.period > self.max_period {
+            self.estimate();
+        }
+    }
+
+    fn estimate(&mut self) {
+        // The first dip below the threshold, or the global minimum if there is none.
+        let mut best = self.min_period;
+        for period in self.min_period..=self.max_period {
+            if self.difference[period] < self.difference[best] {
+                best = period;
+            }
+            if self.difference[period] < THRESHOLD {
+                best = period;
+                while best < self.max_period && self.difference[best + 1] < self.difference[best] {
+                    best += 1;
+                }
+                break;
+            }
+        }
+
+        let confidence = (1.0 - self.difference[best]).max(0.0);
+        self.confidence = confidence;
+        if confidence > 0.0 {
+            self.frequency = self.sample_rate / self.refine(best);
+        }
+    }
+
+    /// Refine period with parabolic interpolation of the difference function around it.
+    fn refine(&self, period: usize) -> Sample {
+        if period <= 1 || period >= self.max_period {
+            return period as Sample;
+        }
+        let a = self.difference[period - 1];
+        let b = self.difference[period];
+        let c = self.difference[period + 1];
+        let denominator = a - 2.0 * b + c;
+        if denominator.abs() > Sample::EPSILON {
+            period as Sample + 0.5 * (a - c) / denominator
+        } else {
+            period as Sample
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! # Pitch shifter
+//!
+//! Real-time pitch shifter built upon the delay line with two read heads. Delay time of each head
+//! sweeps through the window with the speed which makes head read the input faster or slower, and
+//! thus shifts its pitch. When one head jumps back to the beginning of the window the other one is
+//! in the middle of it, and heads are crossfaded by sin² windows to hide the jumps.
+//!
+//! Sources to connect: input, shift in semitones (could be fractional and negative).
+use delay::Delay;
+use interpolation::Interpolation;
+use sample::Sample;
+use std::f64::consts::PI;
+
+pub struct PitchShifter {
+    delay: Delay,
+    /// Position of the first head in the window, 0..1. The second one is shifted by 0.5.
+    phase: Sample,
+    window: Sample,
+    sample_period: Sample,
+}
+
+impl PitchShifter {
+    /// Create pitch shifter with a `window` seconds long delay sweep.
+    /// Longer window means less modulation artefacts on tonal material but more smearing of
+    /// transients.
+    pub fn new(sample_rate: usize, window: Sample) -> Self {
+        PitchShifter {
+            delay: Delay::new(sample_rate, window, Interpolation::Hermite),
+            phase: 0.0,
+            window,
+            sample_period: 1.0 / sample_rate as Sample,
+        }
+    }
+
+    pub fn sample(&mut self, x: Sample, shift: Sample) -> Sample {
+        let ratio = (shift / 12.0).exp2();
+        // Delay grows by (1 - ratio) seconds every second, so that read heads move at `ratio` speed.
+        self.phase =
+            (self.phase + (1.0 - ratio) * self.sample_period / self.window).rem_euclid(1.0);
+        self.delay.push(x);
+        let a = self.phase;
+        let b = (self.phase + 0.5).rem_euclid(1.0);
+        (PI * a).sin().powi(2) * self.delay.tap(a * self.window)
+            + (PI * b).sin().powi(2) * self.delay.tap(b * self.window)
+    }
+}
@@ -1,10 +1,31 @@
 //! # Prelude
 //!
 //! Essentially is re-export of all DSP modules in the library.
+pub use biquad::BPF;
 pub use constant::Constant;
 pub use delay::Delay;
+pub use envelope::EnvelopeFollower;
+pub use expr::Expr;
 pub use feedback::Feedback;
+pub use fft::{Complex, FFT};
+pub use granular::Granular;
+pub use hilbert::{FrequencyShifter, Hilbert};
+pub use interpolation::Interpolation;
+pub use looper::Looper;
+pub use modal::{Modal, Resonance};
+pub use multitap::MultiTap;
+pub use operator::{FMVoice, Operator};
 pub use phasor::Phasor;
+pub use ping_pong::PingPong;
+pub use pitch::PitchTracker;
+pub use pitch_shifter::PitchShifter;
 pub use pure::*;
 pub use rc_filter::{HPF, LPF};
 pub use sample::Sample;
+pub use sampler::Sampler;
+pub use spectral::{CrossSynthesis, SpectralFreeze, SpectralGate};
+pub use stft::Stft;
+pub use trigger::Trigger;
+pub use varispeed::Varispeed;
+pub use vocoder::Vocoder;
+pub use waveguide::Waveguide;
@@ -23,6 +23,131 @@ pub fn div(x: Sample, y: Sample) -> Sample {
     x / y
 }
 
+/// Remainder of x / y which always has the sign of y, so it wraps nicely for negative x.
+pub fn modulo(x: Sample, y: Sample) -> Sample {
+    x - y * (x / y).floor()
+}
+
+pub fn pow(x: Sample, y: Sample) -> Sample {
+    x.powf(y)
+}
+
+pub fn min(x: Sample, y: Sample) -> Sample {
+    x.min(y)
+}
+
+pub fn max(x: Sample, y: Sample) -> Sample {
+    x.max(y)
+}
+
+/// Limit x to the range a..b
+pub fn clamp(x: Sample, a: Sample, b: Sample) -> Sample {
+    x.max(a).min(b)
+}
+
+pub fn abs(x: Sample) -> Sample {
+    x.abs()
+}
+
+/// -1 for negative x, 1 for positive and 0 for zero
+pub fn sign(x: Sample) -> Sample {
+    if x > 0.0 {
+        1.0
+    } else if x < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+pub fn floor(x: Sample) -> Sample {
+    x.floor()
+}
+
+pub fn ceil(x: Sample) -> Sample {
+    x.ceil()
+}
+
+/// Fractional part of x, always in the range 0..1
+pub fn fract(x: Sample) -> Sample {
+    x - x.floor()
+}
+
+pub fn exp(x: Sample) -> Sample {
+    x.exp()
+}
+
+/// Natural logarithm
+pub fn log(x: Sample) -> Sample {
+    x.ln()
+}
+
+pub fn sqrt(x: Sample) -> Sample {
+    x.sqrt()
+}
+
+pub fn tanh(x: Sample) -> Sample {
+    x.tanh()
+}
+
+// Logic
+//
+// Signals are treated as true when positive. True is represented by 1 and false by 0, so results
+// could be used both as gates and as triggers.
+
+fn from_bool(x: bool) -> Sample {
+    if x {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+pub fn lt(x: Sample, y: Sample) -> Sample {
+    from_bool(x < y)
+}
+
+pub fn gt(x: Sample, y: Sample) -> Sample {
+    from_bool(x > y)
+}
+
+pub fn le(x: Sample, y: Sample) -> Sample {
+    from_bool(x <= y)
+}
+
+pub fn ge(x: Sample, y: Sample) -> Sample {
+    from_bool(x >= y)
+}
+
+pub fn eq(x: Sample, y: Sample) -> Sample {
+    from_bool(x == y)
+}
+
+pub fn ne(x: Sample, y: Sample) -> Sample {
+    from_bool(x != y)
+}
+
+pub fn and(x: Sample, y: Sample) -> Sample {
+    from_bool(x > 0.0 && y > 0.0)
+}
+
+pub fn or(x: Sample, y: Sample) -> Sample {
+    from_bool(x > 0.0 || y > 0.0)
+}
+
+pub fn not(x: Sample) -> Sample {
+    from_bool(x <= 0.0)
+}
+
+/// y if x is true, z otherwise
+pub fn select(x: Sample, y: Sample, z: Sample) -> Sample {
+    if x > 0.0 {
+        y
+    } else {
+        z
+    }
+}
+
 // Trigonometry
 
 pub fn sin(x: Sample) -> Sample {
@@ -55,6 +180,33 @@ pub fn circle(x: Sample) -> Sample {
     range(x, -PI, PI)
 }
 
+/// Linear crossfade from x to y controlled by z in the range 0..1
+pub fn mix(x: Sample, y: Sample, z: Sample) -> Sample {
+    x + (y - x) * z
+}
+
+// Conversions
+
+/// MIDI note number to frequency in Hz, A4 (69) being 440 Hz
+pub fn midi_to_hz(x: Sample) -> Sample {
+    440.0 * 2.0_f64.powf((x - 69.0) / 12.0)
+}
+
+/// Frequency in Hz to MIDI note number, A4 (69) being 440 Hz
+pub fn hz_to_midi(x: Sample) -> Sample {
+    69.0 + 12.0 * (x / 440.0).log2()
+}
+
+/// Decibels to amplitude, 0 dB being 1
+pub fn db_to_amp(x: Sample) -> Sample {
+    10.0_f64.powf(x / 20.0)
+}
+
+/// Amplitude to decibels, 1 being 0 dB
+pub fn amp_to_db(x: Sample) -> Sample {
+    20.0 * x.abs().log10()
+}
+
 // Oscillators-ready
 
 /// Connect Phasor to Fn1(sine) to generate sine wave
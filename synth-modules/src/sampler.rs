@@ -0,0 +1,86 @@
+//! # Sampler
+//!
+//! Play back a sample loaded into memory.
+//!
+//! Playback is (re)started when `trigger` crosses zero going up. `rate` controls both speed and
+//! pitch: 1 plays the sample as is, 2 is an octave up, 0.5 is an octave down, negative values play
+//! it backwards. `start` and `end` points select the region to play and are relative to the sample
+//! length, 0 being its beginning and 1 its end. Playback is started from the region boundary
+//! depending on `rate` direction. When `loop` is positive, playback wraps around the region,
+//! otherwise it stops at the boundary.
+//!
+//! Sources to connect: trigger, rate, start, end, loop.
+use sample::Sample;
+use trigger::Trigger;
+
+pub struct Sampler {
+    buffer: Vec<Sample>,
+    /// Ratio of the sample's sample rate to the output one.
+    /// Used to play sample at the original pitch when rate is 1.
+    rate_ratio: Sample,
+    /// Current playback position in frames. Fractional part is used for interpolation.
+    position: Sample,
+    is_playing: bool,
+    trigger: Trigger,
+}
+
+impl Sampler {
+    pub fn new(sample_rate: usize, buffer: Vec<Sample>, buffer_sample_rate: usize) -> Self {
+        Sampler {
+            buffer,
+            rate_ratio: buffer_sample_rate as Sample / sample_rate as Sample,
+            position: 0.0,
+            is_playing: false,
+            trigger: Trigger::new(),
+        }
+    }
+
+    pub fn sample(
+        &mut self,
+        trigger: Sample,
+        rate: Sample,
+        start: Sample,
+        end: Sample,
+        is_looped: Sample,
+    ) -> Sample {
+        let is_triggered = self.trigger.is_triggered(trigger);
+        if self.buffer.is_empty() {
+            return 0.0;
+        }
+        let last_frame = (self.buffer.len() - 1) as Sample;
+        let start = start.clamp(0.0, 1.0) * last_frame;
+        let end = end.clamp(0.0, 1.0) * last_frame;
+        let (from, to) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let rate = rate * self.rate_ratio;
+        if is_triggered {
+            self.position = if rate < 0.0 { to } else { from };
+            self.is_playing = true;
+        }
+        if !self.is_playing {
+            return 0.0;
+        }
+        let output = self.read(self.position);
+        self.position += rate;
+        if self.position < from || self.position > to {
+            if is_looped > 0.0 && to > from {
+                self.position = from + (self.position - from).rem_euclid(to - from);
+            } else {
+                self.is_playing = false;
+            }
+        }
+        output
+    }
+
+    /// Read buffer at fractional position with linear interpolation.
+    fn read(&self, position: Sample) -> Sample {
+        let i = position as usize;
+        let k = position.fract();
+        let a = self.buffer[i];
+        let b = self.buffer[(i + 1).min(self.buffer.len() - 1)];
+        (1.0 - k) * a + k * b
+    }
+}
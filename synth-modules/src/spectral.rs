@@ -0,0 +1,131 @@
+//! # Spectral
+//!
+//! Spectral processing modules built on top of `Stft`.
+//! All of them introduce latency of the FFT size.
+use fft::Complex;
+use sample::Sample;
+use std::f64::consts::PI;
+use stft::Stft;
+
+/// Normalize bin magnitude to the amplitude of the sinusoid producing it.
+/// Peak of the Hann-windowed sinusoid of amplitude A in N-point spectrum is A * N / 4.
+fn amplitude(bin: Complex, size: usize) -> Sample {
+    4.0 * bin.norm() / size as Sample
+}
+
+/// # Spectral freeze
+///
+/// While `freeze` is positive, sustain the spectrum captured at the moment it became positive.
+/// Magnitudes are held and phases keep advancing with the rates captured along with them, which
+/// keeps frozen sound alive instead of buzzing with the frame rate.
+///
+/// Sources to connect: input, freeze.
+pub struct SpectralFreeze {
+    stft: Stft,
+    is_frozen: bool,
+    magnitudes: Vec<Sample>,
+    /// Phases of the previous frame when not frozen, current synthesis phases when frozen.
+    phases: Vec<Sample>,
+    /// Phase increments per frame captured on freeze.
+    increments: Vec<Sample>,
+}
+
+impl SpectralFreeze {
+    pub fn new(size: usize) -> Self {
+        let bins = size / 2 + 1;
+        SpectralFreeze {
+            stft: Stft::new(size, 1),
+            is_frozen: false,
+            magnitudes: vec![0.0; bins],
+            phases: vec![0.0; bins],
+            increments: vec![0.0; bins],
+        }
+    }
+
+    pub fn sample(&mut self, input: Sample, freeze: Sample) -> Sample {
+        let should_freeze = freeze > 0.0;
+        let is_frozen = &mut self.is_frozen;
+        let magnitudes = &mut self.magnitudes;
+        let phases = &mut self.phases;
+        let increments = &mut self.increments;
+        self.stft.sample(&[input], |spectra| {
+            let spectrum = &mut spectra[0];
+            if should_freeze && !*is_frozen {
+                for (k, bin) in spectrum.iter().take(magnitudes.len()).enumerate() {
+                    let phase = bin.arg();
+                    magnitudes[k] = bin.norm();
+                    increments[k] = phase - phases[k];
+                    phases[k] = phase;
+                }
+            }
+            *is_frozen = should_freeze;
+            for (k, bin) in spectrum.iter_mut().take(magnitudes.len()).enumerate() {
+                if *is_frozen {
+                    phases[k] = (phases[k] + increments[k]) % (2.0 * PI);
+                    *bin = Complex::from_polar(magnitudes[k], phases[k]);
+                } else {
+                    phases[k] = bin.arg();
+                }
+            }
+        })
+    }
+}
+
+/// # Spectral gate
+///
+/// Remove spectrum bins which amplitude is below `threshold`, leaving only the strongest partials.
+/// Amplitude is normalized, so threshold of 0.1 removes sinusoids of amplitude below 0.1.
+///
+/// Sources to connect: input, threshold.
+pub struct SpectralGate {
+    stft: Stft,
+}
+
+impl SpectralGate {
+    pub fn new(size: usize) -> Self {
+        SpectralGate {
+            stft: Stft::new(size, 1),
+        }
+    }
+
+    pub fn sample(&mut self, input: Sample, threshold: Sample) -> Sample {
+        self.stft.sample(&[input], |spectra| {
+            let spectrum = &mut spectra[0];
+            let size = spectrum.len();
+            for bin in spectrum.iter_mut().take(size / 2 + 1) {
+                if amplitude(*bin, size) < threshold {
+                    *bin = Complex::default();
+                }
+            }
+        })
+    }
+}
+
+/// # Cross-synthesis
+///
+/// Combine magnitudes of the modulator spectrum with phases of the carrier one. With voice as
+/// a modulator and harmonically rich or noisy carrier it works as a vocoder.
+///
+/// Sources to connect: carrier, modulator.
+pub struct CrossSynthesis {
+    stft: Stft,
+}
+
+impl CrossSynthesis {
+    pub fn new(size: usize) -> Self {
+        CrossSynthesis {
+            stft: Stft::new(size, 2),
+        }
+    }
+
+    pub fn sample(&mut self, carrier: Sample, modulator: Sample) -> Sample {
+        self.stft.sample(&[carrier, modulator], |spectra| {
+            let (carrier, modulator) = spectra.split_at_mut(1);
+            let (carrier, modulator) = (&mut carrier[0], &modulator[0]);
+            let bins = carrier.len() / 2 + 1;
+            for (c, m) in carrier.iter_mut().zip(modulator.iter()).take(bins) {
+                *c = Complex::from_polar(m.norm(), c.arg());
+            }
+        })
+    }
+}
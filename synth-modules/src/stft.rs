@@ -0,0 +1,125 @@
+//! # STFT
+//!
+//! Short-time Fourier transform analysis and resynthesis, the framework for spectral processing.
+//!
+//! Inputs are split into frames of the FFT size overlapping by 3/4, every frame is Hann-windowed and
+//! transformed into spectrum. Spectra are passed to the processing function, and then the spectrum
+//! of the first input is transformed back, windowed again and overlap-added into the output.
+//! Several inputs are supported to allow processing like cross-synthesis, which combines spectra.
+//!
+//! Output is delayed by the FFT size.
+use fft::{Complex, FFT};
+use sample::Sample;
+use std::f64::consts::PI;
+
+/// How many frames overlap at any moment.
+const OVERLAP: usize = 4;
+
+pub struct Stft {
+    fft: FFT,
+    window: Vec<Sample>,
+    /// Ring buffers with the last frame of each input.
+    inputs: Vec<Vec<Sample>>,
+    /// Spectra of the last frame of each input, modified in place by processing.
+    spectra: Vec<Vec<Complex>>,
+    /// Ring buffer accumulating overlapped output frames.
+    output: Vec<Sample>,
+    frame_number: usize,
+    hop: usize,
+    /// Frames since the last hop.
+    hop_frame: usize,
+    /// Compensation of the windows overlap gain.
+    gain: Sample,
+}
+
+impl Stft {
+    /// Create STFT of `channels` inputs with the given FFT size, which must be a power of two.
+    pub fn new(size: usize, channels: usize) -> Self {
+        assert!(size >= OVERLAP, "FFT size must be at least {}", OVERLAP);
+        let window = (0..size)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as Sample / size as Sample).cos())
+            .collect::<Vec<_>>();
+        let hop = size / OVERLAP;
+        // Sum of squared windows (applied on analysis and on synthesis) is constant for any point
+        // of the output, so it's enough to calculate it for the first one.
+        let overlap_gain = (0..OVERLAP)
+            .map(|i| window[i * hop] * window[i * hop])
+            .sum::<Sample>();
+        Stft {
+            fft: FFT::new(size),
+            window,
+            inputs: vec![vec![0.0; size]; channels],
+            spectra: vec![vec![Complex::default(); size]; channels],
+            output: vec![0.0; size],
+            frame_number: 0,
+            hop,
+            hop_frame: 0,
+            gain: if overlap_gain > 0.0 {
+                overlap_gain.recip()
+            } else {
+                1.0
+            },
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Push one sample of every input and return one sample of output.
+    /// `process` is called with spectra of all inputs every time the new frame is complete.
+    /// Spectra contain all N bins, but processing usually needs to care only of the first N/2 + 1
+    /// of them, as the rest are restored from those assuming that output is real.
+    pub fn sample<F>(&mut self, inputs: &[Sample], mut process: F) -> Sample
+    where
+        F: FnMut(&mut [Vec<Complex>]),
+    {
+        let size = self.size();
+        let i = self.frame_number % size;
+        for (buffer, x) in self.inputs.iter_mut().zip(inputs) {
+            buffer[i] = *x;
+        }
+        // Read output before overlap-adding the new frame, as the last sample of the new frame
+        // goes exactly to the slot which is just freed.
+        let output = self.output[i];
+        self.output[i] = 0.0;
+        self.frame_number += 1;
+        self.hop_frame += 1;
+        if self.hop_frame == self.hop {
+            self.hop_frame = 0;
+            self.analyse();
+            process(&mut self.spectra);
+            self.synthesise();
+        }
+        output
+    }
+
+    fn analyse(&mut self) {
+        let size = self.size();
+        let start = self.frame_number % size;
+        for (input, spectrum) in self.inputs.iter().zip(self.spectra.iter_mut()) {
+            for (i, (bin, w)) in spectrum.iter_mut().zip(&self.window).enumerate() {
+                *bin = Complex::new(w * input[(start + i) % size], 0.0);
+            }
+            self.fft.forward(spectrum);
+        }
+    }
+
+    fn synthesise(&mut self) {
+        let size = self.size();
+        let start = self.frame_number % size;
+        let spectrum = match self.spectra.first_mut() {
+            Some(spectrum) => spectrum,
+            None => return,
+        };
+        // Make spectrum conjugate symmetric, so processing could touch only positive frequencies.
+        for k in 1..size / 2 {
+            let bin = spectrum[k];
+            spectrum[size - k] = Complex::new(bin.re, -bin.im);
+        }
+        self.fft.inverse(spectrum);
+        for (i, (bin, w)) in spectrum.iter().zip(&self.window).enumerate() {
+            self.output[(start + i) % size] += self.gain * w * bin.re;
+        }
+    }
+}
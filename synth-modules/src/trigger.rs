@@ -0,0 +1,25 @@
+//! # Trigger
+//!
+//! Rising edge detector. Signal is considered to trigger an event when it crosses zero going up,
+//! i.e. previous sample was non-positive and the current one is positive. This way both gates and
+//! short pulses could be used as triggers, and `phasor` output could be used as a clock.
+
+use sample::Sample;
+
+#[derive(Default)]
+pub struct Trigger {
+    previous: Sample,
+}
+
+impl Trigger {
+    pub fn new() -> Self {
+        Trigger { previous: 0.0 }
+    }
+
+    /// Return true if `x` crossed zero going up since the previous call.
+    pub fn is_triggered(&mut self, x: Sample) -> bool {
+        let is_triggered = self.previous <= 0.0 && x > 0.0;
+        self.previous = x;
+        is_triggered
+    }
+}
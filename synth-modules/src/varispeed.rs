@@ -0,0 +1,78 @@
+//! # Varispeed
+//!
+//! Time-varying resampler, like a tape machine with adjustable speed. Input is continuously
+//! recorded into the buffer and read back at `rate` speed (1 is original speed and pitch, 0.5 is
+//! half speed an octave down, 2 is double speed an octave up) with Hermite interpolation.
+//!
+//! In real time read head drifts away from the write head when slower and catches it up when
+//! faster. When it reaches the buffer bounds it jumps to the other end, and the old head is faded
+//! out while the new one is faded in.
+//!
+//! Sources to connect: input, rate.
+use delay::Delay;
+use interpolation::Interpolation;
+use sample::Sample;
+use std::f64::consts::PI;
+
+/// Crossfade duration (seconds) when read head jumps.
+const FADE: Sample = 0.01;
+/// Max |1 - rate| at which fading out head is guaranteed to stay within the buffer.
+/// It is still safe to go faster, fading out head would just stick to the buffer bound.
+const MAX_DRIFT: Sample = 4.0;
+
+pub struct Varispeed {
+    delay: Delay,
+    /// Current read head delay (seconds).
+    position: Sample,
+    /// Delay of the fading out read head, if any.
+    fading: Option<Sample>,
+    /// Crossfade progress, 0..1.
+    fade: Sample,
+    /// Bounds of the read head delay (seconds).
+    min_delay: Sample,
+    max_delay: Sample,
+    /// Buffer length (seconds).
+    length: Sample,
+    sample_period: Sample,
+}
+
+impl Varispeed {
+    /// Create resampler with a `max_length` seconds long buffer.
+    pub fn new(sample_rate: usize, max_length: Sample) -> Self {
+        let sample_period = 1.0 / sample_rate as Sample;
+        let margin = FADE * MAX_DRIFT + 2.0 * sample_period;
+        let min_delay = margin;
+        let max_delay = (max_length - margin).max(min_delay);
+        Varispeed {
+            delay: Delay::new(sample_rate, max_length, Interpolation::Hermite),
+            position: min_delay,
+            fading: None,
+            fade: 0.0,
+            min_delay,
+            max_delay,
+            length: max_length,
+            sample_period,
+        }
+    }
+
+    pub fn sample(&mut self, input: Sample, rate: Sample) -> Sample {
+        let drift = (1.0 - rate) * self.sample_period;
+        self.position += drift;
+        if self.position < self.min_delay || self.position > self.max_delay {
+            let span = (self.max_delay - self.min_delay).max(self.sample_period);
+            self.fading = Some(self.position);
+            self.fade = 0.0;
+            self.position = self.min_delay + (self.position - self.min_delay).rem_euclid(span);
+        }
+        self.delay.push(input);
+        let mut output = self.delay.tap(self.position);
+        if let Some(fading) = self.fading {
+            let gain = (0.5 * PI * self.fade).sin().powi(2);
+            let fading = (fading + drift).clamp(self.sample_period, self.length);
+            output = gain * output + (1.0 - gain) * self.delay.tap(fading);
+            self.fade += self.sample_period / FADE;
+            self.fading = if self.fade < 1.0 { Some(fading) } else { None };
+        }
+        output
+    }
+}
@@ -0,0 +1,57 @@
+//! # Vocoder
+//!
+//! Classic channel vocoder. Modulator and carrier are split into the same set of bands by band-pass
+//! filters, amplitude of every modulator band is tracked by envelope follower and applied to the
+//! corresponding carrier band. Bands are spaced logarithmically between 100 Hz and 8 kHz.
+//!
+//! Sources to connect: modulator, carrier.
+use biquad::BPF;
+use envelope::{EnvelopeFollower, Mode};
+use sample::Sample;
+
+const MIN_FREQUENCY: Sample = 100.0;
+const MAX_FREQUENCY: Sample = 8000.0;
+/// Envelope followers attack and release (seconds). Fast enough for speech intelligibility and slow
+/// enough to not to modulate carrier by the modulator waveform itself.
+const ATTACK: Sample = 0.005;
+const RELEASE: Sample = 0.02;
+
+struct Band {
+    frequency: Sample,
+    modulator: BPF,
+    carrier: BPF,
+    follower: EnvelopeFollower,
+}
+
+pub struct Vocoder {
+    bands: Vec<Band>,
+    /// Bands Q is chosen to make neighbouring bands cross around -3 dB.
+    q: Sample,
+}
+
+impl Vocoder {
+    pub fn new(sample_rate: usize, bands: usize) -> Self {
+        let bands = bands.max(1);
+        let ratio = (MAX_FREQUENCY / MIN_FREQUENCY).powf(1.0 / (bands as Sample - 1.0).max(1.0));
+        let q = ratio.sqrt() / (ratio - 1.0).max(Sample::EPSILON);
+        let bands = (0..bands)
+            .map(|i| Band {
+                frequency: MIN_FREQUENCY * ratio.powi(i as i32),
+                modulator: BPF::new(sample_rate),
+                carrier: BPF::new(sample_rate),
+                follower: EnvelopeFollower::new(sample_rate, Mode::Peak),
+            }).collect();
+        Vocoder { bands, q }
+    }
+
+    pub fn sample(&mut self, modulator: Sample, carrier: Sample) -> Sample {
+        let q = self.q;
+        self.bands
+            .iter_mut()
+            .map(|band| {
+                let m = band.modulator.sample(modulator, band.frequency, q);
+                let envelope = band.follower.sample(m, ATTACK, RELEASE);
+                envelope * band.carrier.sample(carrier, band.frequency, q)
+            }).sum()
+    }
+}
@@ -0,0 +1,69 @@
+//! # Waveguide
+//!
+//! Digital waveguide physical model. Excitation travels along the delay line tuned to the given
+//! frequency and is fed back through the damping filter, like a wave reflecting from the ends of
+//! a string or a tube.
+//!
+//! String is fixed at both ends, so reflected wave keeps its sign and the loop spans the whole
+//! period. Tube is closed at one end and open at the other one, reflection from the open end
+//! inverts the wave, so the loop spans half of the period and only odd harmonics are present.
+//!
+//! Sources to connect: excitation input, frequency, decay time (seconds to decay by 60 dB),
+//! damping (0 is bright, 1 is dull).
+use delay::Delay;
+use interpolation::Interpolation;
+use sample::Sample;
+
+/// The lowest supported frequency, defines the delay line length.
+const MIN_FREQUENCY: Sample = 20.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    String,
+    Tube,
+}
+
+pub struct Waveguide {
+    delay: Delay,
+    kind: Kind,
+    /// State of the damping filter.
+    output: Sample,
+    sample_period: Sample,
+}
+
+impl Waveguide {
+    pub fn new(sample_rate: usize, kind: Kind) -> Self {
+        Waveguide {
+            delay: Delay::new(sample_rate, 1.0 / MIN_FREQUENCY, Interpolation::Hermite),
+            kind,
+            output: 0.0,
+            sample_period: 1.0 / sample_rate as Sample,
+        }
+    }
+
+    pub fn sample(
+        &mut self,
+        x: Sample,
+        frequency: Sample,
+        decay: Sample,
+        damping: Sample,
+    ) -> Sample {
+        let period = 1.0 / frequency.max(MIN_FREQUENCY);
+        let (length, sign) = match self.kind {
+            Kind::String => (period, 1.0),
+            Kind::Tube => (0.5 * period, -1.0),
+        };
+        // -60 dB in `decay` seconds.
+        let gain = (-6.907_755_278_982_137 * period / decay.max(self.sample_period)).exp();
+        // The loop is read before the current input is pushed, and the damping filter adds
+        // roughly `damping / (1 - damping)` frames of phase delay.
+        let damping = damping.clamp(0.0, 0.99);
+        let filter_delay = damping / (1.0 - damping) * self.sample_period;
+        let reflected = self
+            .delay
+            .tap((length - self.sample_period - filter_delay).max(0.0));
+        self.output = (1.0 - damping) * reflected + damping * self.output;
+        self.delay.push(x + sign * gain * self.output);
+        reflected
+    }
+}